@@ -0,0 +1,107 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use cry_rs::{
+    ec::{multi_mul_with, Curve, Group, MultiMulAlgorithm, Point},
+    ecdsa::SigningKey,
+    mpi::Mpi,
+    sha256::Sha256,
+};
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+fn random_point(grp: &Group, rng: &mut ChaCha20Rng) -> Point {
+    grp.mul_generator(&Mpi::random_below(grp.order(), rng))
+}
+
+fn fixed_base(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let mut group = c.benchmark_group("fixed_base");
+    for curve in [Curve::Secp256r1, Curve::Secp384r1] {
+        let grp = Group::new(curve);
+        let table = grp.generator_table();
+        let k = Mpi::random_below(grp.order(), &mut rng);
+        let name = format!("{curve:?}");
+
+        group.bench_function(BenchmarkId::new("backend", &name), |b| {
+            b.iter(|| grp.mul_generator(&k))
+        });
+        group.bench_function(BenchmarkId::new("table", &name), |b| {
+            b.iter(|| table.mul(&k))
+        });
+    }
+    group.finish();
+}
+
+// u1 * G + u2 * Q, as computed by the signature verification.
+fn double_mul(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let mut group = c.benchmark_group("double_mul");
+    for curve in [Curve::Secp256r1, Curve::Secp384r1] {
+        let grp = Group::new(curve);
+        let table = grp.generator_table();
+        let u1 = Mpi::random_below(grp.order(), &mut rng);
+        let u2 = Mpi::random_below(grp.order(), &mut rng);
+        let q = random_point(&grp, &mut rng);
+        let terms = [(u2.clone(), q.clone())];
+        let name = format!("{curve:?}");
+
+        group.bench_function(BenchmarkId::new("backend", &name), |b| {
+            b.iter(|| grp.add(&grp.mul_generator(&u1), &grp.mul(&u2, &q)))
+        });
+        group.bench_function(BenchmarkId::new("table_straus", &name), |b| {
+            b.iter(|| table.mul_add(&u1, &terms))
+        });
+    }
+    group.finish();
+}
+
+fn multi_mul(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let grp = Group::new(Curve::Secp256r1);
+    let mut group = c.benchmark_group("multi_mul");
+    group.sample_size(10);
+    for n in [4, 32, 256] {
+        let terms: Vec<_> = (0..n)
+            .map(|_| {
+                let k = Mpi::random_below(grp.order(), &mut rng);
+                (k, random_point(&grp, &mut rng))
+            })
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("backend", n), &terms, |b, terms| {
+            b.iter(|| {
+                terms.iter().fold(Point::identity(), |acc, (k, point)| {
+                    grp.add(&acc, &grp.mul(k, point))
+                })
+            })
+        });
+        for algo in [MultiMulAlgorithm::Straus, MultiMulAlgorithm::Pippenger] {
+            let id = BenchmarkId::new(format!("{algo:?}"), n);
+            group.bench_with_input(id, &terms, |b, terms| {
+                b.iter(|| multi_mul_with(&grp, terms, algo))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn ecdsa_verify(c: &mut Criterion) {
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+    let key = SigningKey::random(Group::new(Curve::Secp256r1), &mut rng);
+    let vk = key.verifying_key();
+    let sig = key.sign::<Sha256>(b"benchmark").unwrap();
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"benchmark");
+    let digest = hasher.digest();
+    let mut group = c.benchmark_group("ecdsa_verify");
+
+    group.bench_function(BenchmarkId::new("backend", "Secp256r1"), |b| {
+        b.iter(|| vk.verify_prehash_backend(digest, &sig))
+    });
+    group.bench_function(BenchmarkId::new("table_straus", "Secp256r1"), |b| {
+        b.iter(|| vk.verify_prehash(digest, &sig))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, fixed_base, double_mul, multi_mul, ecdsa_verify);
+criterion_main!(benches);
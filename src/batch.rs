@@ -0,0 +1,104 @@
+//! Batch verification work split across threads.
+//!
+//! Numbers can't be sent to other threads, so the items hold plain data and
+//! each thread decodes what it needs into its own state.
+
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+// Minimum number of items handed to a thread.
+const MIN_CHUNK: usize = 16;
+
+fn chunk_len(items: usize) -> usize {
+    let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    items.div_ceil(threads).max(MIN_CHUNK)
+}
+
+/// Result of `verify` for each item, `state` is called once by each thread
+/// to build the values shared by its verifications.
+pub(crate) fn verify_each<T, S, I, F>(items: &[T], state: I, verify: F) -> Vec<bool>
+where
+    T: Sync,
+    I: Fn() -> S + Sync,
+    F: Fn(&S, &T) -> bool + Sync,
+{
+    let run = |chunk: &[T]| {
+        let state = state();
+        chunk
+            .iter()
+            .map(|item| verify(&state, item))
+            .collect::<Vec<_>>()
+    };
+    let chunk = chunk_len(items.len());
+    if items.len() <= chunk {
+        return run(items);
+    }
+    let run = &run;
+    thread::scope(|s| {
+        let workers: Vec<_> = items
+            .chunks(chunk)
+            .map(|chunk| s.spawn(move || run(chunk)))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().expect("Verification thread panicked"))
+            .collect()
+    })
+}
+
+/// Checks that `verify` holds for all the items, stopping at the first
+/// failure. `state` is as for `verify_each`.
+pub(crate) fn verify_all<T, S, I, F>(items: &[T], state: I, verify: F) -> bool
+where
+    T: Sync,
+    I: Fn() -> S + Sync,
+    F: Fn(&S, &T) -> bool + Sync,
+{
+    let chunk = chunk_len(items.len());
+    if items.len() <= chunk {
+        let state = state();
+        return items.iter().all(|item| verify(&state, item));
+    }
+    let failed = AtomicBool::new(false);
+    let (state, verify, failed_ref) = (&state, &verify, &failed);
+    thread::scope(|s| {
+        for chunk in items.chunks(chunk) {
+            s.spawn(move || {
+                let state = state();
+                for item in chunk {
+                    if failed_ref.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    if !verify(&state, item) {
+                        failed_ref.store(true, Ordering::Relaxed);
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    !failed.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_results() {
+        let items: Vec<u32> = (0..1000).collect();
+        let odd = |m: &u32, x: &u32| x % m == 1;
+        let two = || 2;
+
+        let res = verify_each(&items, two, odd);
+        assert_eq!(res.len(), items.len());
+        assert!(res.iter().zip(&items).all(|(&r, x)| r == (x % 2 == 1)));
+        assert!(verify_all(&items, two, |_, &x| x < 1000));
+        assert!(!verify_all(&items, two, |_, &x| x != 777));
+        assert!(verify_all(&items[..0], two, odd));
+        assert_eq!(verify_each(&items[..3], two, odd), [false, true, false]);
+    }
+}
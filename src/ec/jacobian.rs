@@ -0,0 +1,220 @@
+//! Inversion free point arithmetic in Jacobian coordinates.
+//!
+//! The formulas are the ones of the Explicit-Formulas Database for short
+//! Weierstrass curves (`dbl-2007-bl`, `add-2007-bl` and `madd-2007-bl`).
+//! Running time depends on the operands, so they are used with public
+//! scalars only.
+
+use super::{Group, Point};
+use crate::mpi::{Modulus, Residue};
+
+/// Point `(X / Z^2, Y / Z^3)`, the identity has `Z = 0`.
+#[derive(Clone)]
+pub(super) struct Jacobian<'m> {
+    x: Residue<'m>,
+    y: Residue<'m>,
+    z: Residue<'m>,
+}
+
+impl Jacobian<'_> {
+    pub fn is_identity(&self) -> bool {
+        self.z.value().is_zero()
+    }
+}
+
+/// Curve arithmetic over the field of a group.
+pub(super) struct Field<'m> {
+    modulus: &'m Modulus,
+    a: Residue<'m>,
+}
+
+impl<'m> Field<'m> {
+    pub fn new(modulus: &'m Modulus, group: &Group) -> Self {
+        Field {
+            modulus,
+            a: modulus.residue(group.a()),
+        }
+    }
+
+    pub fn identity(&self) -> Jacobian<'m> {
+        Jacobian {
+            x: self.modulus.one(),
+            y: self.modulus.one(),
+            z: self.modulus.zero(),
+        }
+    }
+
+    pub fn jacobian(&self, point: &Point) -> Jacobian<'m> {
+        if point.is_identity() {
+            return self.identity();
+        }
+        Jacobian {
+            x: self.modulus.residue(point.x()),
+            y: self.modulus.residue(point.y()),
+            z: self.modulus.one(),
+        }
+    }
+
+    pub fn to_affine(&self, point: &Jacobian<'m>) -> Point {
+        match point.z.inv() {
+            Some(z_inv) => self.scale(point, &z_inv),
+            None => Point::identity(),
+        }
+    }
+
+    /// Converts all the points with a single inversion (Montgomery's trick).
+    pub fn batch_to_affine(&self, points: &[Jacobian<'m>]) -> Vec<Point> {
+        // prods[i] is the product of the non zero `Z` before the i-th point.
+        let mut prods = Vec::with_capacity(points.len());
+        let mut acc = self.modulus.one();
+        for point in points {
+            prods.push(acc.clone());
+            if !point.is_identity() {
+                acc = &acc * &point.z;
+            }
+        }
+        let mut acc_inv = acc.inv().expect("Non zero product");
+        let mut res = vec![Point::identity(); points.len()];
+        for (i, point) in points.iter().enumerate().rev() {
+            if point.is_identity() {
+                continue;
+            }
+            res[i] = self.scale(point, &(&acc_inv * &prods[i]));
+            acc_inv = &acc_inv * &point.z;
+        }
+        res
+    }
+
+    // Affine point from the inverse of `Z`.
+    fn scale(&self, point: &Jacobian<'m>, z_inv: &Residue<'m>) -> Point {
+        let z_inv2 = z_inv.square();
+        let x = &point.x * &z_inv2;
+        let y = &(&point.y * &z_inv2) * z_inv;
+        Point::new(x.into_value(), y.into_value())
+    }
+
+    pub fn neg(&self, point: &Jacobian<'m>) -> Jacobian<'m> {
+        Jacobian {
+            x: point.x.clone(),
+            y: point.y.neg(),
+            z: point.z.clone(),
+        }
+    }
+
+    pub fn double(&self, point: &Jacobian<'m>) -> Jacobian<'m> {
+        if point.is_identity() || point.y.value().is_zero() {
+            return self.identity();
+        }
+        let xx = point.x.square();
+        let yy = point.y.square();
+        let yyyy = yy.square();
+        // S = 4 * X * YY, M = 3 * XX + a * ZZ^2
+        let s = double(&double(&(&point.x * &yy)));
+        let mut m = &double(&xx) + &xx;
+        if !self.a.value().is_zero() {
+            m = &m + &(&self.a * &point.z.square().square());
+        }
+        let x = &m.square() - &double(&s);
+        let y = &(&m * &(&s - &x)) - &double(&double(&double(&yyyy)));
+        let z = double(&(&point.y * &point.z));
+        Jacobian { x, y, z }
+    }
+
+    pub fn add(&self, p1: &Jacobian<'m>, p2: &Jacobian<'m>) -> Jacobian<'m> {
+        if p1.is_identity() {
+            return p2.clone();
+        }
+        if p2.is_identity() {
+            return p1.clone();
+        }
+        let z1z1 = p1.z.square();
+        let z2z2 = p2.z.square();
+        let u1 = &p1.x * &z2z2;
+        let u2 = &p2.x * &z1z1;
+        let s1 = &(&p1.y * &p2.z) * &z2z2;
+        let s2 = &(&p2.y * &p1.z) * &z1z1;
+        let z = &p1.z * &p2.z;
+        self.add_with(p1, u1, u2, s1, s2, z)
+    }
+
+    /// Addition of a point with `Z = 1`, as returned by `jacobian`.
+    pub fn add_affine(&self, p1: &Jacobian<'m>, p2: &Jacobian<'m>) -> Jacobian<'m> {
+        if p1.is_identity() {
+            return p2.clone();
+        }
+        if p2.is_identity() {
+            return p1.clone();
+        }
+        let z1z1 = p1.z.square();
+        let u2 = &p2.x * &z1z1;
+        let s2 = &(&p2.y * &p1.z) * &z1z1;
+        self.add_with(p1, p1.x.clone(), u2, p1.y.clone(), s2, p1.z.clone())
+    }
+
+    // Common tail of the additions, with both points scaled to the same `Z`
+    // and `z` the product of the two `Z`.
+    fn add_with(
+        &self,
+        p1: &Jacobian<'m>,
+        u1: Residue<'m>,
+        u2: Residue<'m>,
+        s1: Residue<'m>,
+        s2: Residue<'m>,
+        z: Residue<'m>,
+    ) -> Jacobian<'m> {
+        let h = &u2 - &u1;
+        let r = &s2 - &s1;
+        if h.value().is_zero() {
+            return if r.value().is_zero() {
+                self.double(p1)
+            } else {
+                self.identity()
+            };
+        }
+        let hh = h.square();
+        let hhh = &h * &hh;
+        let v = &u1 * &hh;
+        let x = &(&r.square() - &hhh) - &double(&v);
+        let y = &(&r * &(&v - &x)) - &(&s1 * &hhh);
+        Jacobian { x, y, z: &z * &h }
+    }
+}
+
+fn double<'m>(x: &Residue<'m>) -> Residue<'m> {
+    x + x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ec::Curve, mpi::Mpi};
+
+    #[test]
+    fn matches_backend() {
+        for curve in [Curve::Secp256r1, Curve::Secp256k1, Curve::Bp256r1] {
+            let grp = Group::new(curve);
+            let modulus = Modulus::new(grp.p().clone()).unwrap();
+            let f = Field::new(&modulus, &grp);
+            let g = grp.generator();
+            let g2 = grp.mul_generator(&Mpi::from(2i64));
+            let g3 = grp.mul_generator(&Mpi::from(3i64));
+            let jg = f.jacobian(g);
+
+            let dbl = f.double(&jg);
+            assert_eq!(f.to_affine(&dbl), g2);
+            assert_eq!(f.to_affine(&f.add_affine(&dbl, &jg)), g3);
+            assert_eq!(f.to_affine(&f.add(&jg, &dbl)), g3);
+            // Doubling through the addition
+            assert_eq!(
+                f.to_affine(&f.add(&dbl, &dbl)),
+                f.to_affine(&f.double(&dbl))
+            );
+            assert!(f.add(&jg, &f.neg(&jg)).is_identity());
+            assert!(f.add_affine(&dbl, &f.neg(&f.jacobian(&g2))).is_identity());
+            assert_eq!(
+                f.batch_to_affine(&[dbl, f.identity(), jg]),
+                [g2, Point::identity(), g.clone()]
+            );
+        }
+    }
+}
@@ -0,0 +1,580 @@
+//! Elliptic curve groups in short Weierstrass form and their points.
+
+use crate::mpi::Mpi;
+use core::{
+    cell::OnceCell,
+    fmt,
+    mem::{size_of, MaybeUninit},
+};
+use cry_sys::bindings::{
+    cry_ecp, cry_ecp_add, cry_ecp_grp, cry_ecp_grp_init, cry_ecp_grp_load, cry_ecp_mul,
+    CRY_ECP_GRP_BP256R1, CRY_ECP_GRP_BP384R1, CRY_ECP_GRP_BP512R1, CRY_ECP_GRP_SECP192K1,
+    CRY_ECP_GRP_SECP192R1, CRY_ECP_GRP_SECP224K1, CRY_ECP_GRP_SECP224R1, CRY_ECP_GRP_SECP256K1,
+    CRY_ECP_GRP_SECP256R1, CRY_ECP_GRP_SECP384R1, CRY_ECP_GRP_SECP521R1,
+};
+use rand_core::{CryptoRng, RngCore};
+use std::rc::Rc;
+
+mod jacobian;
+mod multi;
+mod sec1;
+mod table;
+
+pub use multi::{multi_mul, multi_mul_with, MultiMulAlgorithm};
+pub use table::GeneratorTable;
+
+// Point and group parameters mirror the layout of the backend types, so that
+// they can be passed by pointer and their numbers are released by `Mpi` drop.
+const _: () = assert!(size_of::<Point>() == size_of::<cry_ecp>());
+const _: () = assert!(size_of::<GroupParams>() == size_of::<cry_ecp_grp>());
+
+type TableCell = Rc<OnceCell<GeneratorTable>>;
+
+thread_local! {
+    // Generator tables of the named curves, shared by all their groups.
+    static CURVE_TABLES: [TableCell; Curve::COUNT] = Default::default();
+}
+
+// Backend failures here are allocation failures.
+macro_rules! checked {
+    ($op:expr) => {
+        if unsafe { $op } != 0 {
+            panic!("Out of memory error");
+        }
+    };
+}
+
+/// Built-in named curves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Curve {
+    Secp192r1,
+    Secp224r1,
+    Secp256r1,
+    Secp384r1,
+    Secp521r1,
+    Secp192k1,
+    Secp224k1,
+    Secp256k1,
+    Bp256r1,
+    Bp384r1,
+    Bp512r1,
+}
+
+impl Curve {
+    const COUNT: usize = Curve::Bp512r1 as usize + 1;
+
+    fn id(self) -> u32 {
+        match self {
+            Curve::Secp192r1 => CRY_ECP_GRP_SECP192R1,
+            Curve::Secp224r1 => CRY_ECP_GRP_SECP224R1,
+            Curve::Secp256r1 => CRY_ECP_GRP_SECP256R1,
+            Curve::Secp384r1 => CRY_ECP_GRP_SECP384R1,
+            Curve::Secp521r1 => CRY_ECP_GRP_SECP521R1,
+            Curve::Secp192k1 => CRY_ECP_GRP_SECP192K1,
+            Curve::Secp224k1 => CRY_ECP_GRP_SECP224K1,
+            Curve::Secp256k1 => CRY_ECP_GRP_SECP256K1,
+            Curve::Bp256r1 => CRY_ECP_GRP_BP256R1,
+            Curve::Bp384r1 => CRY_ECP_GRP_BP384R1,
+            Curve::Bp512r1 => CRY_ECP_GRP_BP512R1,
+        }
+    }
+}
+
+/// Curve point in affine coordinates.
+///
+/// The backend marks finite points with `z = 1` and the identity with `z = 0`.
+#[derive(Clone, Debug)]
+#[repr(C)]
+pub struct Point {
+    x: Mpi,
+    y: Mpi,
+    z: Mpi,
+}
+
+impl Point {
+    /// Affine point, use `Group::is_on_curve` to validate it.
+    pub fn new(x: Mpi, y: Mpi) -> Self {
+        Point {
+            x,
+            y,
+            z: Mpi::from(1i64),
+        }
+    }
+
+    /// The point at infinity.
+    pub fn identity() -> Self {
+        Point {
+            x: Mpi::new(),
+            y: Mpi::new(),
+            z: Mpi::new(),
+        }
+    }
+
+    pub fn x(&self) -> &Mpi {
+        &self.x
+    }
+
+    pub fn y(&self) -> &Mpi {
+        &self.y
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const cry_ecp {
+        self as *const Point as *const cry_ecp
+    }
+
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut cry_ecp {
+        self as *mut Point as *mut cry_ecp
+    }
+}
+
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.is_identity(), other.is_identity()) {
+            (false, false) => self.x == other.x && self.y == other.y,
+            (identity, other_identity) => identity == other_identity,
+        }
+    }
+}
+
+// Layout of the backend group.
+#[derive(Clone, Debug, PartialEq)]
+#[repr(C)]
+pub(crate) struct GroupParams {
+    p: Mpi,
+    a: Mpi,
+    b: Mpi,
+    g: Point,
+    n: Mpi,
+}
+
+/// Curve `y^2 = x^3 + a*x + b` over the prime field `p`, with a generator of
+/// prime order `n`.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "GroupComponents", into = "GroupComponents")
+)]
+pub struct Group {
+    params: GroupParams,
+    h: Mpi,
+    // Named curve the group was loaded from.
+    curve: Option<Curve>,
+    // Generator table, computed on first use and shared by the clones.
+    table: TableCell,
+}
+
+impl Group {
+    pub fn new(curve: Curve) -> Self {
+        let mut params = MaybeUninit::<GroupParams>::uninit();
+        let ptr = params.as_mut_ptr() as *mut cry_ecp_grp;
+        checked!(cry_ecp_grp_init(ptr));
+        checked!(cry_ecp_grp_load(ptr, curve.id() as _));
+        let params = unsafe { params.assume_init() };
+        // round((p + 1) / n), the named curves orders exceed 4 * sqrt(p) so
+        // it's the only cofactor within the Hasse bound.
+        let h = (&(&params.p + 1u64) + &params.n.shr(1)) / &params.n;
+        Group {
+            params,
+            h,
+            curve: Some(curve),
+            table: CURVE_TABLES.with(|tables| tables[curve as usize].clone()),
+        }
+    }
+
+    // Group with its own generator table.
+    fn with_params(params: GroupParams, h: Mpi) -> Self {
+        Group {
+            params,
+            h,
+            curve: None,
+            table: TableCell::default(),
+        }
+    }
+
+    /// Group from explicit domain parameters: field prime `p`, coefficients
+    /// `a` and `b`, generator `g` of prime order `n` and cofactor `h`.
+    ///
+    /// The parameters are validated as in SEC 1 (3.1.1.2.1), except for the
+    /// MOV and anomalous curve checks. The points count isn't computed, `h`
+    /// is only checked to place `h * n` within the Hasse bound and to not be
+    /// a multiple of `n`.
+    pub fn from_params(p: Mpi, a: Mpi, b: Mpi, g: Point, n: Mpi, h: Mpi) -> Result<Self, String> {
+        // Short Weierstrass form requires characteristic other than 2 and 3.
+        if p.bits_count() < 3 || !p.is_prime() {
+            return Err("Invalid field prime".into());
+        }
+        let a = a.rem_euclid(&p);
+        let b = b.rem_euclid(&p);
+        // 4 * a^3 + 27 * b^2 != 0
+        let disc = &(&(&a * &a) * &a) * 4u64 + &(&b * &b) * 27u64;
+        if disc.rem_euclid(&p).is_zero() {
+            return Err("Singular curve".into());
+        }
+        if !n.is_prime() {
+            return Err("Invalid group order".into());
+        }
+        // |h * n - (p + 1)| <= 2 * sqrt(p)
+        let dist = &(&h * &n) - &(&p + 1u64);
+        if h.is_negative() || h.is_zero() || &dist * &dist > &p * 4u64 {
+            return Err("Invalid cofactor".into());
+        }
+        let grp = Group::with_params(GroupParams { p, a, b, g, n }, h);
+        if !grp.is_on_curve(grp.generator()) {
+            return Err("Generator not on curve".into());
+        }
+        if !grp.mul_generator(grp.order()).is_identity() {
+            return Err("Invalid generator order".into());
+        }
+        // A cofactor multiple of n would be a multiple of the order of G.
+        if grp.mul_generator(&grp.h).is_identity() {
+            return Err("Invalid cofactor".into());
+        }
+        Ok(grp)
+    }
+
+    /// Field prime.
+    pub fn p(&self) -> &Mpi {
+        &self.params.p
+    }
+
+    pub fn a(&self) -> &Mpi {
+        &self.params.a
+    }
+
+    pub fn b(&self) -> &Mpi {
+        &self.params.b
+    }
+
+    pub fn generator(&self) -> &Point {
+        &self.params.g
+    }
+
+    /// Generator order.
+    pub fn order(&self) -> &Mpi {
+        &self.params.n
+    }
+
+    /// Number of curve points divided by the generator order.
+    pub fn cofactor(&self) -> Mpi {
+        self.h.clone()
+    }
+
+    /// Checks that `point` is a finite point satisfying the curve equation.
+    pub fn is_on_curve(&self, point: &Point) -> bool {
+        let p = self.p();
+        let (x, y) = (point.x(), point.y());
+        if point.is_identity() || x.is_negative() || y.is_negative() || x >= p || y >= p {
+            return false;
+        }
+        let lhs = (y * y) % p;
+        let rhs = (&(&(&(x * x) * x) + &(self.a() * x)) + self.b()).rem_euclid(p);
+        lhs == rhs
+    }
+
+    pub fn add(&self, p1: &Point, p2: &Point) -> Point {
+        let mut res = Point::identity();
+        checked!(cry_ecp_add(
+            res.as_mut_ptr(),
+            p1.as_ptr(),
+            p2.as_ptr(),
+            self.as_ptr()
+        ));
+        res
+    }
+
+    /// Scalar multiplication `k * point`, `k` must be non-negative.
+    pub fn mul(&self, k: &Mpi, point: &Point) -> Point {
+        let mut res = Point::identity();
+        checked!(cry_ecp_mul(
+            res.as_mut_ptr(),
+            point.as_ptr(),
+            k as *const Mpi as *const _,
+            self.as_ptr()
+        ));
+        res
+    }
+
+    /// Scalar multiplication of the generator.
+    pub fn mul_generator(&self, k: &Mpi) -> Point {
+        self.mul(k, self.generator())
+    }
+
+    /// Checks that `k` is in the range `[1, n)`.
+    pub(crate) fn is_valid_scalar(&self, k: &Mpi) -> bool {
+        !k.is_zero() && !k.is_negative() && k < self.order()
+    }
+
+    /// Uniform scalar in the range `[1, n)`.
+    pub(crate) fn random_scalar<R: RngCore + CryptoRng>(&self, rng: &mut R) -> Mpi {
+        loop {
+            let k = Mpi::random_below(self.order(), rng);
+            if !k.is_zero() {
+                return k;
+            }
+        }
+    }
+
+    pub(crate) fn params(&self) -> &GroupParams {
+        &self.params
+    }
+
+    pub(crate) fn as_ptr(&self) -> *const cry_ecp_grp {
+        &self.params as *const GroupParams as *const cry_ecp_grp
+    }
+
+    pub(crate) fn to_raw(&self) -> RawGroup {
+        let g = self.generator();
+        let params = [self.p(), self.a(), self.b(), &g.x, &g.y, self.order()];
+        let params = params.map(|x| (x.is_negative(), x.to_bytes_be()));
+        RawGroup(self.curve, params, self.h.to_bytes_be())
+    }
+}
+
+impl fmt::Debug for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Group")
+            .field("p", self.p())
+            .field("a", self.a())
+            .field("b", self.b())
+            .field("g", self.generator())
+            .field("n", self.order())
+            .finish()
+    }
+}
+
+impl PartialEq for Group {
+    fn eq(&self, other: &Self) -> bool {
+        self.params == other.params && self.h == other.h
+    }
+}
+
+/// Group parameters as signed big-endian bytes, which unlike the numbers can
+/// be sent to other threads. Named curves are loaded again instead, so that
+/// they share the generator tables of the thread.
+pub(crate) struct RawGroup(Option<Curve>, [(bool, Vec<u8>); 6], Vec<u8>);
+
+impl RawGroup {
+    pub(crate) fn group(&self) -> Group {
+        if let Some(curve) = self.0 {
+            return Group::new(curve);
+        }
+        let [p, a, b, gx, gy, n] = self.1.clone().map(|(negative, bytes)| {
+            let x = Mpi::from_bytes_be(&bytes);
+            if negative {
+                -x
+            } else {
+                x
+            }
+        });
+        let params = GroupParams {
+            p,
+            a,
+            b,
+            g: Point::new(gx, gy),
+            n,
+        };
+        Group::with_params(params, Mpi::from_bytes_be(&self.2))
+    }
+}
+
+// Serialized form, validated on deserialization.
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct GroupComponents {
+    p: Mpi,
+    a: Mpi,
+    b: Mpi,
+    gx: Mpi,
+    gy: Mpi,
+    n: Mpi,
+    h: Mpi,
+}
+
+#[cfg(feature = "serde")]
+impl From<Group> for GroupComponents {
+    fn from(grp: Group) -> Self {
+        let h = grp.h;
+        let GroupParams { p, a, b, g, n } = grp.params;
+        GroupComponents {
+            p,
+            a,
+            b,
+            gx: g.x,
+            gy: g.y,
+            n,
+            h,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<GroupComponents> for Group {
+    type Error = String;
+
+    fn try_from(c: GroupComponents) -> Result<Self, Self::Error> {
+        Group::from_params(c.p, c.a, c.b, Point::new(c.gx, c.gy), c.n, c.h)
+    }
+}
+
+/// Parameters of y^2 = x^3 + 2x + 20 over F_10007, with 4 * 2521 points, as
+/// `(p, a, b, G, n, h)`.
+#[cfg(test)]
+pub(crate) fn toy_params() -> (Mpi, Mpi, Mpi, Point, Mpi, Mpi) {
+    let int = |x: i64| Mpi::from(x);
+    let g = Point::new(int(7345), int(5479));
+    (int(10007), int(2), int(20), g, int(2521), int(4))
+}
+
+/// Small group with a cofactor for tests, see [`toy_params`].
+#[cfg(test)]
+pub(crate) fn toy_group() -> Group {
+    let (p, a, b, g, n, h) = toy_params();
+    Group::from_params(p, a, b, g, n, h).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P256_GX: &str = "6b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+    const P256_GY: &str = "4fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+    // 2 * G
+    const P256_2GX: &str = "7cf27b188d034f7e8a52380304b51ac3c08969e277f21b35a60b48fc47669978";
+    const P256_2GY: &str = "07775510db8ed040293d9ac69f7430dbba7dade63ce982299e04b79d227873d1";
+
+    const SM2_P: &str = "fffffffeffffffffffffffffffffffffffffffff00000000ffffffffffffffff";
+    const SM2_A: &str = "fffffffeffffffffffffffffffffffffffffffff00000000fffffffffffffffc";
+    const SM2_B: &str = "28e9fa9e9d9f5e344d5a9e4bcf6509a7f39789f515ab8f92ddbcbd414d940e93";
+    const SM2_GX: &str = "32c4ae2c1f1981195f9904466a39c9948fe30bbff2660be1715a4589334c74c7";
+    const SM2_GY: &str = "bc3736a2f4f6779c59bdcee36b692153d0a9877cc62a474002df32e52139f0a0";
+    const SM2_N: &str = "fffffffeffffffffffffffffffffffff7203df6b21c6052b53bbf40939d54123";
+
+    fn point(x: &str, y: &str) -> Point {
+        Point::new(Mpi::from_hex(x).unwrap(), Mpi::from_hex(y).unwrap())
+    }
+
+    fn int(x: i64) -> Mpi {
+        Mpi::from(x)
+    }
+
+    #[test]
+    fn named_group() {
+        let grp = Group::new(Curve::Secp256r1);
+
+        assert_eq!(grp.generator(), &point(P256_GX, P256_GY));
+        assert_eq!(grp.order().bits_count(), 256);
+        assert!(grp.is_on_curve(grp.generator()));
+        assert!(!grp.is_on_curve(&point(P256_GX, P256_2GY)));
+        assert!(!grp.is_on_curve(&Point::identity()));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let grp = Group::new(Curve::Secp256r1);
+        let g = grp.generator();
+        let two_g = point(P256_2GX, P256_2GY);
+
+        assert_eq!(grp.add(g, g), two_g);
+        assert_eq!(grp.mul_generator(&Mpi::from(2i64)), two_g);
+        assert!(grp.mul_generator(grp.order()).is_identity());
+        assert_eq!(grp.add(&two_g, &Point::identity()), two_g);
+
+        for curve in [Curve::Secp384r1, Curve::Secp256k1, Curve::Bp512r1] {
+            let grp = Group::new(curve);
+            let k = Mpi::from(12345i64);
+            assert!(grp.is_on_curve(&grp.mul_generator(&k)));
+        }
+    }
+
+    #[test]
+    fn custom_group() {
+        let sm2 = Group::from_params(
+            Mpi::from_hex(SM2_P).unwrap(),
+            Mpi::from_hex(SM2_A).unwrap(),
+            Mpi::from_hex(SM2_B).unwrap(),
+            point(SM2_GX, SM2_GY),
+            Mpi::from_hex(SM2_N).unwrap(),
+            int(1),
+        )
+        .unwrap();
+        let k = int(12345);
+        assert!(sm2.is_on_curve(&sm2.mul_generator(&k)));
+        assert_eq!(sm2.cofactor(), int(1));
+
+        let toy = toy_group();
+        assert_eq!(toy.cofactor(), int(4));
+        assert_eq!(
+            toy.mul_generator(&int(4)),
+            Point::new(int(0x23a5), int(0x1f3f))
+        );
+        assert!(toy.mul_generator(&int(2521)).is_identity());
+
+        for curve in [Curve::Secp256r1, Curve::Secp224k1, Curve::Bp512r1] {
+            let grp = Group::new(curve);
+            assert_eq!(grp.cofactor(), int(1));
+            let custom = Group::from_params(
+                grp.p().clone(),
+                grp.a().clone(),
+                grp.b().clone(),
+                grp.generator().clone(),
+                grp.order().clone(),
+                int(1),
+            )
+            .unwrap();
+            assert_eq!(custom.mul_generator(&k), grp.mul_generator(&k));
+        }
+    }
+
+    #[test]
+    fn invalid_params() {
+        let (p, a, b, g, n, h) = toy_params();
+        let custom = |p: i64, a: i64, b: i64, g: &Point, n: i64, h: i64| {
+            Group::from_params(int(p), int(a), int(b), g.clone(), int(n), int(h))
+        };
+        assert!(Group::from_params(p, a, b, g.clone(), n, h).is_ok());
+        assert!(custom(10007, -10005, 20, &g, 2521, 4).is_ok());
+
+        // Composite field
+        assert!(custom(10005, 2, 20, &g, 2521, 4).is_err());
+        // x^3 - 3x + 2 = (x - 1)^2 * (x + 2)
+        assert!(custom(10007, -3, 2, &g, 2521, 4).is_err());
+        assert!(custom(10007, 2, 20, &Point::new(int(7345), int(5478)), 2521, 4).is_err());
+        assert!(custom(10007, 2, 20, &Point::identity(), 2521, 4).is_err());
+        // Point of order 4 * n
+        assert!(custom(10007, 2, 20, &Point::new(int(2), int(557)), 2521, 4).is_err());
+        assert!(custom(10007, 2, 20, &g, 2523, 4).is_err());
+        assert!(custom(10007, 2, 20, &g, 2531, 4).is_err());
+        // Cofactors outside the Hasse bound
+        assert!(custom(10007, 2, 20, &g, 13, 4).is_err());
+        assert!(custom(10007, 2, 20, &g, 2521, 2).is_err());
+        assert!(custom(10007, 2, 20, &g, 2521, -4).is_err());
+    }
+
+    #[test]
+    fn small_order() {
+        // y^2 = x^3 + 2x + 1 over F_10007, with 4 * 23 * 109 points
+        let g = Point::new(int(750), int(991));
+        let custom =
+            |h: i64| Group::from_params(int(10007), int(2), int(1), g.clone(), int(23), int(h));
+
+        let small = custom(436).unwrap();
+        assert_eq!(small.cofactor(), int(436));
+        assert!(small.mul_generator(&int(23)).is_identity());
+        // Multiple of n within the Hasse bound, 437 * 23 = 10051
+        assert!(custom(437).is_err());
+        assert!(custom(500).is_err());
+    }
+
+    #[test]
+    fn identity() {
+        assert!(Point::identity().is_identity());
+        assert_eq!(Point::identity(), Point::identity());
+        assert_ne!(Point::identity(), point(P256_GX, P256_GY));
+    }
+}
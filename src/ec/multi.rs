@@ -0,0 +1,285 @@
+//! Multi-scalar multiplication `k1 * P1 + ... + kn * Pn`.
+
+use super::{
+    jacobian::{Field, Jacobian},
+    Group, Point,
+};
+use crate::mpi::{Modulus, Mpi};
+
+/// Multi-scalar multiplication algorithms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MultiMulAlgorithm {
+    /// Interleaved wNAF (Straus-Shamir), the doublings are shared and each
+    /// scalar costs an addition every `w + 1` bits.
+    Straus,
+    /// Bucket method (Pippenger), the cost per scalar decreases with the
+    /// number of terms.
+    Pippenger,
+}
+
+// Number of terms from which the bucket method is faster.
+const PIPPENGER_THRESHOLD: usize = 128;
+
+// wNAF window of the Straus method.
+const STRAUS_WINDOW: usize = 5;
+
+/// Computes `k1 * P1 + ... + kn * Pn` with the algorithm best suited for the
+/// number of terms.
+///
+/// Running time depends on the scalars, which must not be secret. Negative
+/// scalars are allowed.
+pub fn multi_mul(group: &Group, terms: &[(Mpi, Point)]) -> Point {
+    multi_mul_with(group, terms, select(terms.len()))
+}
+
+fn select(terms: usize) -> MultiMulAlgorithm {
+    if terms >= PIPPENGER_THRESHOLD {
+        MultiMulAlgorithm::Pippenger
+    } else {
+        MultiMulAlgorithm::Straus
+    }
+}
+
+/// Computes `k1 * P1 + ... + kn * Pn` with the given algorithm.
+pub fn multi_mul_with(group: &Group, terms: &[(Mpi, Point)], algo: MultiMulAlgorithm) -> Point {
+    let modulus = Modulus::new(group.p().clone()).expect("Field prime");
+    let f = Field::new(&modulus, group);
+    f.to_affine(&multi_mul_jacobian_with(&f, terms, algo))
+}
+
+pub(super) fn multi_mul_jacobian<'m>(f: &Field<'m>, terms: &[(Mpi, Point)]) -> Jacobian<'m> {
+    multi_mul_jacobian_with(f, terms, select(terms.len()))
+}
+
+fn multi_mul_jacobian_with<'m>(
+    f: &Field<'m>,
+    terms: &[(Mpi, Point)],
+    algo: MultiMulAlgorithm,
+) -> Jacobian<'m> {
+    match algo {
+        MultiMulAlgorithm::Straus => straus(f, terms),
+        MultiMulAlgorithm::Pippenger => pippenger(f, terms),
+    }
+}
+
+// Affine points with non-negative scalars, negating the point instead.
+fn normalize<'m>(f: &Field<'m>, terms: &[(Mpi, Point)]) -> Vec<(Mpi, Jacobian<'m>)> {
+    terms
+        .iter()
+        .map(|(k, point)| {
+            let point = f.jacobian(point);
+            if k.is_negative() {
+                (k.abs(), f.neg(&point))
+            } else {
+                (k.clone(), point)
+            }
+        })
+        .collect()
+}
+
+// Width-`w` non-adjacent form of a non-negative `k`, least significant
+// digit first. Non zero digits are odd, in `(-2^(w-1), 2^(w-1))`, and
+// followed by at least `w - 1` zeros.
+fn wnaf(k: &Mpi, w: usize) -> Vec<i8> {
+    let mut k = k.clone();
+    let mut digits = Vec::with_capacity(k.bits_count() + 1);
+    while !k.is_zero() {
+        let mut d = 0;
+        if k.is_odd() {
+            d = (0..w).filter(|&i| k.bit(i)).map(|i| 1i64 << i).sum::<i64>();
+            if d >= 1 << (w - 1) {
+                d -= 1 << w;
+            }
+            k = &k - d;
+        }
+        digits.push(d as i8);
+        k = k.shr(1);
+    }
+    digits
+}
+
+// Odd multiples `P, 3P, ..., (2^(w-1) - 1)P` with `Z = 1`, for the wNAF
+// digits of width `w`.
+fn odd_multiples<'m>(f: &Field<'m>, points: &[&Jacobian<'m>], w: usize) -> Vec<Vec<Jacobian<'m>>> {
+    let count = 1 << (w - 2);
+    let mut multiples = Vec::with_capacity(points.len() * count);
+    for point in points {
+        let dbl = f.double(point);
+        let mut acc = (*point).clone();
+        for _ in 0..count {
+            let next = f.add(&acc, &dbl);
+            multiples.push(acc);
+            acc = next;
+        }
+    }
+    let affine = f.batch_to_affine(&multiples);
+    affine
+        .chunks(count)
+        .map(|chunk| chunk.iter().map(|p| f.jacobian(p)).collect())
+        .collect()
+}
+
+// acc + d * P, with `d` a wNAF digit and `table` the odd multiples of P.
+fn add_digit<'m>(f: &Field<'m>, acc: &Jacobian<'m>, table: &[Jacobian<'m>], d: i8) -> Jacobian<'m> {
+    match d {
+        0 => acc.clone(),
+        d if d > 0 => f.add_affine(acc, &table[d as usize / 2]),
+        d => f.add_affine(acc, &f.neg(&table[d.unsigned_abs() as usize / 2])),
+    }
+}
+
+fn straus<'m>(f: &Field<'m>, terms: &[(Mpi, Point)]) -> Jacobian<'m> {
+    let terms = normalize(f, terms);
+    let points: Vec<_> = terms.iter().map(|(_, p)| p).collect();
+    let tables = odd_multiples(f, &points, STRAUS_WINDOW);
+    let nafs: Vec<_> = terms.iter().map(|(k, _)| wnaf(k, STRAUS_WINDOW)).collect();
+    let len = nafs.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut acc = f.identity();
+    for i in (0..len).rev() {
+        acc = f.double(&acc);
+        for (naf, table) in nafs.iter().zip(&tables) {
+            if let Some(&d) = naf.get(i) {
+                acc = add_digit(f, &acc, table, d);
+            }
+        }
+    }
+    acc
+}
+
+// Bucket width for `n` terms, about `ln(n)` bits.
+fn pippenger_window(n: usize) -> usize {
+    match n {
+        0..=3 => 1,
+        4..=31 => 3,
+        _ => (n as f64).ln() as usize + 2,
+    }
+}
+
+fn pippenger<'m>(f: &Field<'m>, terms: &[(Mpi, Point)]) -> Jacobian<'m> {
+    let terms = normalize(f, terms);
+    let c = pippenger_window(terms.len());
+    let bits = terms.iter().map(|(k, _)| k.bits_count()).max().unwrap_or(0);
+    let windows = bits.div_ceil(c);
+
+    let mut acc = f.identity();
+    let mut buckets = vec![f.identity(); (1 << c) - 1];
+    for w in (0..windows).rev() {
+        for _ in 0..c {
+            acc = f.double(&acc);
+        }
+        buckets.fill(f.identity());
+        for (k, point) in &terms {
+            let digit = (0..c)
+                .filter(|&i| k.bit(w * c + i))
+                .map(|i| 1 << i)
+                .sum::<usize>();
+            if digit != 0 {
+                buckets[digit - 1] = f.add_affine(&buckets[digit - 1], point);
+            }
+        }
+        // sum(i * B_i) as the sum of the partial sums from the top bucket.
+        let mut running = f.identity();
+        let mut sum = f.identity();
+        for bucket in buckets.iter().rev() {
+            running = f.add(&running, bucket);
+            sum = f.add(&sum, &running);
+        }
+        acc = f.add(&acc, &sum);
+    }
+    acc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::Curve;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // Sum of the backend scalar multiplications.
+    fn naive(grp: &Group, terms: &[(Mpi, Point)]) -> Point {
+        terms.iter().fold(Point::identity(), |acc, (k, point)| {
+            let point = if k.is_negative() {
+                let q = grp.mul(&k.abs(), point);
+                if q.is_identity() {
+                    q
+                } else {
+                    Point::new(q.x().clone(), grp.p() - q.y())
+                }
+            } else {
+                grp.mul(k, point)
+            };
+            grp.add(&acc, &point)
+        })
+    }
+
+    fn random_terms(grp: &Group, n: usize, rng: &mut ChaCha20Rng) -> Vec<(Mpi, Point)> {
+        (0..n)
+            .map(|_| {
+                let k = Mpi::random_below(grp.order(), rng);
+                let point = grp.mul_generator(&Mpi::random_below(grp.order(), rng));
+                (k, point)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wnaf_digits() {
+        for k in [1i64, 7, 255, 1000, 0x5a5a5a5a, 123456789] {
+            let naf = wnaf(&Mpi::from(k), 5);
+            let value = naf
+                .iter()
+                .rev()
+                .fold(0i64, |acc, &d| 2 * acc + i64::from(d));
+            assert_eq!(value, k);
+            for (i, &d) in naf.iter().enumerate() {
+                assert!(d == 0 || (d % 2 != 0 && d.abs() < 16));
+                if d != 0 {
+                    assert!(naf[i + 1..].iter().take(4).all(|&d| d == 0));
+                }
+            }
+        }
+        assert!(wnaf(&Mpi::new(), 5).is_empty());
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        for curve in [Curve::Secp256r1, Curve::Secp256k1, Curve::Secp384r1] {
+            let grp = Group::new(curve);
+            for n in [1, 2, 5, 40] {
+                let terms = random_terms(&grp, n, &mut rng);
+                let expected = naive(&grp, &terms);
+
+                for algo in [MultiMulAlgorithm::Straus, MultiMulAlgorithm::Pippenger] {
+                    assert_eq!(multi_mul_with(&grp, &terms, algo), expected);
+                }
+                assert_eq!(multi_mul(&grp, &terms), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn special_terms() {
+        let grp = Group::new(Curve::Secp256r1);
+        let g = grp.generator().clone();
+        let n = grp.order().clone();
+
+        for algo in [MultiMulAlgorithm::Straus, MultiMulAlgorithm::Pippenger] {
+            assert!(multi_mul_with(&grp, &[], algo).is_identity());
+            // k * G + (n - k) * G
+            let k = Mpi::from(123456i64);
+            let terms = [(k.clone(), g.clone()), (&n - &k, g.clone())];
+            assert!(multi_mul_with(&grp, &terms, algo).is_identity());
+            // 3 * G - 1 * G + 0 * G + 5 * O
+            let terms = [
+                (Mpi::from(3i64), g.clone()),
+                (Mpi::from(-1i64), g.clone()),
+                (Mpi::new(), g.clone()),
+                (Mpi::from(5i64), Point::identity()),
+            ];
+            let expected = grp.mul_generator(&Mpi::from(2i64));
+            assert_eq!(multi_mul_with(&grp, &terms, algo), expected);
+        }
+    }
+}
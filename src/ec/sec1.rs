@@ -0,0 +1,179 @@
+//! SEC 1 (2.3.3 and 2.3.4) point encoding.
+
+use super::{Group, Point};
+use crate::mpi::Mpi;
+
+const TAG_IDENTITY: u8 = 0x00;
+const TAG_EVEN_Y: u8 = 0x02;
+const TAG_ODD_Y: u8 = 0x03;
+const TAG_UNCOMPRESSED: u8 = 0x04;
+
+impl Point {
+    /// SEC 1 encoding, `0x04 || x || y` or, if `compressed`, the `x`
+    /// coordinate prefixed by `0x02` or `0x03` according to the `y` parity.
+    /// Coordinates are reduced modulo the `group` field prime and are as long
+    /// as it. The identity is encoded as a single zero byte.
+    pub fn to_sec1(&self, group: &Group, compressed: bool) -> Vec<u8> {
+        if self.is_identity() {
+            return vec![TAG_IDENTITY];
+        }
+        let p = group.p();
+        let len = p.bytes_count();
+        let (x, y) = (self.x.rem_euclid(p), self.y.rem_euclid(p));
+        let mut out = Vec::with_capacity(1 + 2 * len);
+        if compressed {
+            out.push(if y.is_odd() { TAG_ODD_Y } else { TAG_EVEN_Y });
+            push_fixed(&mut out, &x, len);
+        } else {
+            out.push(TAG_UNCOMPRESSED);
+            push_fixed(&mut out, &x, len);
+            push_fixed(&mut out, &y, len);
+        }
+        out
+    }
+
+    /// Decodes a compressed or uncompressed SEC 1 point of `group`.
+    ///
+    /// Points not on the curve are rejected, and so is the identity, which is
+    /// never a valid key.
+    pub fn from_sec1(group: &Group, bytes: &[u8]) -> Result<Self, String> {
+        let p = group.p();
+        let len = p.bytes_count();
+        let (&tag, coords) = bytes.split_first().ok_or("Empty point encoding")?;
+        let point = match tag {
+            TAG_IDENTITY if coords.is_empty() => return Err("Identity point".into()),
+            TAG_EVEN_Y | TAG_ODD_Y if coords.len() == len => {
+                let x = Mpi::from_bytes_be(coords);
+                if &x >= p {
+                    return Err("Invalid point".into());
+                }
+                let alpha = (&(&(&(&x * &x) * &x) + &(group.a() * &x)) + group.b()).rem_euclid(p);
+                let beta = alpha.mod_sqrt(p).ok_or("Invalid point")?;
+                let odd = tag == TAG_ODD_Y;
+                let y = if beta.is_odd() == odd {
+                    beta
+                } else if beta.is_zero() {
+                    return Err("Invalid point".into());
+                } else {
+                    p - &beta
+                };
+                Point::new(x, y)
+            }
+            TAG_UNCOMPRESSED if coords.len() == 2 * len => {
+                let (x, y) = coords.split_at(len);
+                Point::new(Mpi::from_bytes_be(x), Mpi::from_bytes_be(y))
+            }
+            _ => return Err("Invalid point encoding".into()),
+        };
+        if !group.is_on_curve(&point) {
+            return Err("Invalid point".into());
+        }
+        Ok(point)
+    }
+}
+
+// Appends `x` big-endian, left padded to `len` bytes, `x` must fit.
+fn push_fixed(out: &mut Vec<u8>, x: &Mpi, len: usize) {
+    let bytes = x.to_bytes_be();
+    out.resize(out.len() + len - bytes.len(), 0);
+    out.extend_from_slice(&bytes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::Curve;
+
+    const P256_G_COMPRESSED: &str =
+        "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296";
+    const P256_G_UNCOMPRESSED: &str = "046b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c2964fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5";
+    const K256_G_COMPRESSED: &str =
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    #[test]
+    fn generator_encoding() {
+        let grp = Group::new(Curve::Secp256r1);
+        let g = grp.generator();
+
+        assert_eq!(hex::encode(g.to_sec1(&grp, true)), P256_G_COMPRESSED);
+        assert_eq!(hex::encode(g.to_sec1(&grp, false)), P256_G_UNCOMPRESSED);
+        for enc in [P256_G_COMPRESSED, P256_G_UNCOMPRESSED] {
+            assert_eq!(
+                &Point::from_sec1(&grp, &hex::decode(enc).unwrap()).unwrap(),
+                g
+            );
+        }
+
+        let grp = Group::new(Curve::Secp256k1);
+        assert_eq!(
+            hex::encode(grp.generator().to_sec1(&grp, true)),
+            K256_G_COMPRESSED
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        for curve in [
+            Curve::Secp192r1,
+            Curve::Secp224r1,
+            Curve::Secp521r1,
+            Curve::Bp384r1,
+        ] {
+            let grp = Group::new(curve);
+            let len = grp.p().bytes_count();
+
+            for k in [2i64, 3, 1000, 123456789] {
+                let point = grp.mul_generator(&Mpi::from(k));
+                let compressed = point.to_sec1(&grp, true);
+                let uncompressed = point.to_sec1(&grp, false);
+
+                assert_eq!(compressed.len(), 1 + len);
+                assert_eq!(uncompressed.len(), 1 + 2 * len);
+                assert_eq!(Point::from_sec1(&grp, &compressed).unwrap(), point);
+                assert_eq!(Point::from_sec1(&grp, &uncompressed).unwrap(), point);
+            }
+        }
+    }
+
+    #[test]
+    fn unreduced_coordinates() {
+        let grp = Group::new(Curve::Secp256r1);
+        let (g, p) = (grp.generator(), grp.p());
+
+        let shifted = Point::new(g.x() + p, g.y() - p);
+        assert_eq!(hex::encode(shifted.to_sec1(&grp, true)), P256_G_COMPRESSED);
+        assert_eq!(
+            hex::encode(shifted.to_sec1(&grp, false)),
+            P256_G_UNCOMPRESSED
+        );
+        let shifted = Point::new(g.x().clone(), g.y() + &(p * 3u64));
+        assert_eq!(hex::encode(shifted.to_sec1(&grp, true)), P256_G_COMPRESSED);
+    }
+
+    #[test]
+    fn invalid_encodings() {
+        let grp = Group::new(Curve::Secp256r1);
+        let mut compressed = hex::decode(P256_G_COMPRESSED).unwrap();
+        let mut uncompressed = hex::decode(P256_G_UNCOMPRESSED).unwrap();
+
+        assert_eq!(Point::identity().to_sec1(&grp, true), [0]);
+        assert!(Point::from_sec1(&grp, &[0]).is_err());
+        assert!(Point::from_sec1(&grp, &[]).is_err());
+        assert!(Point::from_sec1(&grp, &compressed[..32]).is_err());
+        // Hybrid encoding
+        uncompressed[0] = 0x07;
+        assert!(Point::from_sec1(&grp, &uncompressed).is_err());
+        // Off the curve
+        uncompressed[0] = 0x04;
+        uncompressed[64] ^= 1;
+        assert!(Point::from_sec1(&grp, &uncompressed).is_err());
+        // x = p is out of the field
+        compressed[1..].copy_from_slice(&grp.p().to_bytes_be());
+        assert!(Point::from_sec1(&grp, &compressed).is_err());
+        // x^3 - 3x + b is a square for x = 0 but not for x = 1
+        compressed[1..].fill(0);
+        assert!(Point::from_sec1(&grp, &compressed).is_ok());
+        compressed[32] = 1;
+        assert!(Point::from_sec1(&grp, &compressed).is_err());
+    }
+}
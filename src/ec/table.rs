@@ -0,0 +1,178 @@
+//! Fixed-base multiplication of a group generator.
+
+use super::{
+    jacobian::{Field, Jacobian},
+    multi::multi_mul_jacobian,
+    Group, Point,
+};
+use crate::mpi::{Modulus, Mpi};
+
+// Bits of the scalar consumed by each table row.
+const WINDOW: usize = 4;
+
+/// Precomputed multiples `j * 16^i * G` of a group generator.
+///
+/// A multiplication by `G` is then a sequence of additions of table entries,
+/// one every 4 scalar bits and without doublings. As for `multi_mul`, running
+/// time depends on the scalars, which must not be secret.
+#[derive(Debug)]
+pub struct GeneratorTable {
+    group: Group,
+    modulus: Modulus,
+    // rows[i][j - 1] = j * 16^i * G, affine.
+    rows: Vec<Vec<Point>>,
+}
+
+impl GeneratorTable {
+    pub fn new(group: &Group) -> Self {
+        let modulus = Modulus::new(group.p().clone()).expect("Field prime");
+        let f = Field::new(&modulus, group);
+        let rows = group.order().bits_count().div_ceil(WINDOW);
+        let row_len = (1 << WINDOW) - 1;
+
+        let mut entries = Vec::with_capacity(rows * row_len);
+        let mut base = f.jacobian(group.generator());
+        for _ in 0..rows {
+            let mut acc = base.clone();
+            for _ in 0..row_len {
+                let next = f.add(&acc, &base);
+                entries.push(acc);
+                acc = next;
+            }
+            // 16 * base
+            base = acc;
+        }
+        let rows = f
+            .batch_to_affine(&entries)
+            .chunks(row_len)
+            .map(<[Point]>::to_vec)
+            .collect();
+        GeneratorTable {
+            // A clone would share the cell holding this table.
+            group: Group::with_params(group.params.clone(), group.h.clone()),
+            modulus,
+            rows,
+        }
+    }
+
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    /// Scalar multiplication of the generator, `k` is taken modulo the order.
+    pub fn mul(&self, k: &Mpi) -> Point {
+        let f = Field::new(&self.modulus, &self.group);
+        f.to_affine(&self.mul_jacobian(&f, k))
+    }
+
+    /// Computes `k * G + k1 * P1 + ... + kn * Pn`, sharing the final
+    /// conversion to affine coordinates.
+    pub fn mul_add(&self, k: &Mpi, terms: &[(Mpi, Point)]) -> Point {
+        let f = Field::new(&self.modulus, &self.group);
+        let acc = self.mul_jacobian(&f, k);
+        f.to_affine(&f.add(&acc, &multi_mul_jacobian(&f, terms)))
+    }
+
+    fn mul_jacobian<'m>(&self, f: &Field<'m>, k: &Mpi) -> Jacobian<'m> {
+        let k = k.rem_euclid(self.group.order());
+        let mut acc = f.identity();
+        for (i, row) in self.rows.iter().enumerate() {
+            let digit = (0..WINDOW)
+                .filter(|&b| k.bit(i * WINDOW + b))
+                .map(|b| 1 << b)
+                .sum::<usize>();
+            if digit != 0 {
+                acc = f.add_affine(&acc, &f.jacobian(&row[digit - 1]));
+            }
+        }
+        acc
+    }
+}
+
+impl Group {
+    /// Generator table of this group, computed on first use and then shared
+    /// by its clones. The named curves tables are shared by all their groups
+    /// of the same thread.
+    pub fn generator_table(&self) -> &GeneratorTable {
+        self.table.get_or_init(|| GeneratorTable::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::Curve;
+    use core::ptr;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+    use std::rc::Rc;
+
+    #[test]
+    fn fixed_base() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        for curve in [Curve::Secp256r1, Curve::Secp256k1, Curve::Secp521r1] {
+            let grp = Group::new(curve);
+            let table = GeneratorTable::new(&grp);
+
+            for k in [1i64, 2, 15, 16, 17, 0xffff] {
+                let k = Mpi::from(k);
+                assert_eq!(table.mul(&k), grp.mul_generator(&k));
+            }
+            let k = Mpi::random_below(grp.order(), &mut rng);
+            assert_eq!(table.mul(&k), grp.mul_generator(&k));
+            assert!(table.mul(&Mpi::new()).is_identity());
+            assert!(table.mul(grp.order()).is_identity());
+            let n_minus_one = grp.order() - 1u64;
+            assert_eq!(table.mul(&n_minus_one), table.mul(&Mpi::from(-1i64)));
+        }
+    }
+
+    #[test]
+    fn fixed_base_with_terms() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let grp = Group::new(Curve::Secp384r1);
+        let table = grp.generator_table();
+        let u1 = Mpi::random_below(grp.order(), &mut rng);
+        let u2 = Mpi::random_below(grp.order(), &mut rng);
+        let q = grp.mul_generator(&Mpi::random_below(grp.order(), &mut rng));
+        let expected = grp.add(&grp.mul_generator(&u1), &grp.mul(&u2, &q));
+
+        assert_eq!(table.mul_add(&u1, &[(u2.clone(), q.clone())]), expected);
+        let terms = [(u2.clone(), q.clone()), (Mpi::from(2i64), q.clone())];
+        let two_q = grp.mul(&Mpi::from(2i64), &q);
+        assert_eq!(table.mul_add(&u1, &terms), grp.add(&expected, &two_q));
+        assert_eq!(table.mul_add(&u1, &[]), grp.mul_generator(&u1));
+        // u1 * G - u1 * G
+        let terms = [(u1.neg(), grp.generator().clone())];
+        assert!(table.mul_add(&u1, &terms).is_identity());
+    }
+
+    #[test]
+    fn shared_tables() {
+        let k1 = Group::new(Curve::Secp256k1);
+        let k1_other = Group::new(Curve::Secp256k1);
+        let r1 = Group::new(Curve::Secp256r1);
+
+        assert!(ptr::eq(k1.generator_table(), k1_other.generator_table()));
+        assert!(!ptr::eq(k1.generator_table(), r1.generator_table()));
+
+        let custom = Group::with_params(r1.params.clone(), r1.h.clone());
+        let custom_clone = custom.clone();
+        assert!(ptr::eq(
+            custom.generator_table(),
+            custom_clone.generator_table()
+        ));
+        assert!(!ptr::eq(custom.generator_table(), r1.generator_table()));
+        // The table group doesn't keep the table alive.
+        assert_eq!(Rc::strong_count(&custom.table), 2);
+
+        // Groups rebuilt by the batch threads.
+        let raw = r1.to_raw().group();
+        assert!(ptr::eq(raw.generator_table(), r1.generator_table()));
+        let raw_custom = custom.to_raw().group();
+        assert_eq!(raw_custom, custom);
+        assert!(!ptr::eq(
+            raw_custom.generator_table(),
+            custom.generator_table()
+        ));
+    }
+}
@@ -0,0 +1,132 @@
+//! Elliptic curve Diffie-Hellman over the `ec` groups.
+
+use crate::{
+    ec::{Group, Point},
+    mpi::Mpi,
+};
+use rand_core::{CryptoRng, RngCore};
+
+/// Secret scalar `d` with its public point `d * G`.
+#[derive(Clone)]
+pub struct SecretKey {
+    group: Group,
+    d: Mpi,
+    public: Point,
+}
+
+impl SecretKey {
+    /// Key with secret scalar `d`, which must be in the range `[1, n)`.
+    pub fn new(group: Group, d: Mpi) -> Result<Self, String> {
+        if !group.is_valid_scalar(&d) {
+            return Err("Invalid private key".into());
+        }
+        let public = group.mul_generator(&d);
+        Ok(SecretKey { group, d, public })
+    }
+
+    pub fn random<R: RngCore + CryptoRng>(group: Group, rng: &mut R) -> Self {
+        let d = group.random_scalar(rng);
+        Self::new(group, d).expect("Scalar in range")
+    }
+
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    pub fn public_key(&self) -> &Point {
+        &self.public
+    }
+
+    /// Shared secret with the owner of the `peer` public point, the `x`
+    /// coordinate of `h * d * peer` as long as the field prime.
+    ///
+    /// Multiplying by the cofactor `h` (SEC 1, 3.3.2) prevents a peer from
+    /// confining the secret to a small subgroup.
+    pub fn diffie_hellman(&self, peer: &Point) -> Result<Vec<u8>, String> {
+        if !self.group.is_on_curve(peer) {
+            return Err("Invalid public key".into());
+        }
+        let k = &self.group.cofactor() * &self.d;
+        let z = self.group.mul(&k, peer);
+        if z.is_identity() {
+            return Err("Invalid public key".into());
+        }
+        // Compressed encoding without the tag is the fixed length `x`.
+        Ok(z.to_sec1(&self.group, true).split_off(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::{toy_group, Curve};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // Shared secret computed with OpenSSL.
+    const P256_D1: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+    const P256_D2: &str = "0102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20";
+    const P256_Z: &str = "cf551a5f5d50b264e06ee9c4f7f541aa0318be11d12577b3857c8b5c625f935a";
+
+    fn key(group: &Group, d: &str) -> SecretKey {
+        SecretKey::new(group.clone(), Mpi::from_hex(d).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn p256_agreement() {
+        let grp = Group::new(Curve::Secp256r1);
+        let (k1, k2) = (key(&grp, P256_D1), key(&grp, P256_D2));
+
+        let z1 = k1.diffie_hellman(k2.public_key()).unwrap();
+        let z2 = k2.diffie_hellman(k1.public_key()).unwrap();
+
+        assert_eq!(hex::encode(&z1), P256_Z);
+        assert_eq!(z1, z2);
+    }
+
+    #[test]
+    fn random_agreement() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        for curve in [Curve::Secp256k1, Curve::Secp521r1] {
+            let grp = Group::new(curve);
+            let k1 = SecretKey::random(grp.clone(), &mut rng);
+            let k2 = SecretKey::random(grp.clone(), &mut rng);
+
+            let z = k1.diffie_hellman(k2.public_key()).unwrap();
+            assert_eq!(z.len(), grp.p().bytes_count());
+            assert_eq!(z, k2.diffie_hellman(k1.public_key()).unwrap());
+        }
+    }
+
+    #[test]
+    fn custom_group_cofactor() {
+        let int = |x: i64| Mpi::from(x);
+        let grp = toy_group();
+        let k1 = SecretKey::new(grp.clone(), int(1234)).unwrap();
+        let k2 = SecretKey::new(grp, int(777)).unwrap();
+
+        // x(4 * 1234 * 777 * G)
+        assert_eq!(
+            hex::encode(k1.diffie_hellman(k2.public_key()).unwrap()),
+            "06bc"
+        );
+        assert_eq!(
+            hex::encode(k2.diffie_hellman(k1.public_key()).unwrap()),
+            "06bc"
+        );
+        // Point of order 4, wiped by the cofactor
+        let small = Point::new(int(940), int(9937));
+        assert!(k1.diffie_hellman(&small).is_err());
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let grp = Group::new(Curve::Secp256r1);
+        let k = key(&grp, P256_D1);
+
+        assert!(SecretKey::new(grp.clone(), Mpi::new()).is_err());
+        assert!(SecretKey::new(grp.clone(), grp.order().clone()).is_err());
+        assert!(k.diffie_hellman(&Point::identity()).is_err());
+        let off_curve = Point::new(k.public_key().x().clone(), Mpi::from(1i64));
+        assert!(k.diffie_hellman(&off_curve).is_err());
+    }
+}
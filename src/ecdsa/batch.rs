@@ -0,0 +1,133 @@
+use super::{verify_digest, Signature, VerifyingKey};
+use crate::{
+    batch,
+    ec::{Group, Point, RawGroup},
+    mpi::Mpi,
+    traits::Hasher,
+};
+
+/// Item of a batch verification, a signature over the `H` digest of a
+/// message.
+pub type BatchItem<'a> = (&'a VerifyingKey, &'a [u8], &'a Signature);
+
+// Batch item with the numbers as big-endian bytes, to be decoded by the
+// verifying thread.
+struct RawItem<'a> {
+    // Index of the key group.
+    group: usize,
+    q: [Vec<u8>; 2],
+    msg: &'a [u8],
+    // None if a number is negative, which is never valid.
+    sig: Option<[Vec<u8>; 2]>,
+}
+
+impl RawItem<'_> {
+    fn verify<H: Hasher>(&self, groups: &[Group]) -> bool {
+        let Some([r, s]) = &self.sig else {
+            return false;
+        };
+        let q = Point::new(
+            Mpi::from_bytes_be(&self.q[0]),
+            Mpi::from_bytes_be(&self.q[1]),
+        );
+        let sig = Signature::new(Mpi::from_bytes_be(r), Mpi::from_bytes_be(s));
+        let digest = H::digest_parts(&[self.msg]);
+        verify_digest(&groups[self.group], &q, &digest, &sig)
+    }
+}
+
+// Distinct groups of the batch and the items referring to them.
+fn encode<'a>(items: &[BatchItem<'a>]) -> (Vec<RawGroup>, Vec<RawItem<'a>>) {
+    let mut groups: Vec<&Group> = Vec::new();
+    let raw = items
+        .iter()
+        .map(|&(key, msg, sig)| {
+            let group = match groups.iter().position(|&g| g == key.group()) {
+                Some(i) => i,
+                None => {
+                    groups.push(key.group());
+                    groups.len() - 1
+                }
+            };
+            let q = key.point();
+            let (r, s) = (sig.r(), sig.s());
+            RawItem {
+                group,
+                q: [q.x().to_bytes_be(), q.y().to_bytes_be()],
+                msg,
+                sig: (!r.is_negative() && !s.is_negative())
+                    .then(|| [r.to_bytes_be(), s.to_bytes_be()]),
+            }
+        })
+        .collect();
+    (groups.into_iter().map(Group::to_raw).collect(), raw)
+}
+
+/// Checks that all the signatures of the batch are valid.
+///
+/// An ECDSA signature holds only the `x` coordinate of its nonce point, so
+/// the signatures can't be combined into a single check. Instead they are
+/// verified independently, split across the available cores, and the
+/// verification stops at the first invalid one.
+pub fn verify_batch<H: Hasher>(items: &[BatchItem]) -> bool {
+    let (groups, items) = encode(items);
+    let state = || groups.iter().map(RawGroup::group).collect::<Vec<_>>();
+    batch::verify_all(&items, state, |groups, item| item.verify::<H>(groups))
+}
+
+/// Validity of each signature of the batch, see `verify_batch`.
+pub fn verify_batch_each<H: Hasher>(items: &[BatchItem]) -> Vec<bool> {
+    let (groups, items) = encode(items);
+    let state = || groups.iter().map(RawGroup::group).collect::<Vec<_>>();
+    batch::verify_each(&items, state, |groups, item| item.verify::<H>(groups))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ec::{Curve, Group},
+        ecdsa::SigningKey,
+        sha256::Sha256,
+    };
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn batch_results() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let keys: Vec<_> = [Curve::Secp256r1, Curve::Secp256k1]
+            .into_iter()
+            .cycle()
+            .take(40)
+            .map(|curve| SigningKey::random(Group::new(curve), &mut rng))
+            .collect();
+        let msgs: Vec<_> = (0..keys.len()).map(|i| format!("device {i}")).collect();
+        let sigs: Vec<_> = keys
+            .iter()
+            .zip(&msgs)
+            .map(|(key, msg)| key.sign::<Sha256>(msg).unwrap())
+            .collect();
+        let vks: Vec<_> = keys.iter().map(SigningKey::verifying_key).collect();
+        let mut items: Vec<BatchItem> = vks
+            .iter()
+            .zip(&msgs)
+            .zip(&sigs)
+            .map(|((vk, msg), sig)| (vk, msg.as_bytes(), sig))
+            .collect();
+
+        assert!(verify_batch::<Sha256>(&items));
+        assert!(verify_batch_each::<Sha256>(&items).iter().all(|&ok| ok));
+        assert!(verify_batch::<Sha256>(&[]));
+
+        items[7].1 = b"tampered";
+        items[30].2 = &sigs[31];
+        assert!(!verify_batch::<Sha256>(&items));
+        let res = verify_batch_each::<Sha256>(&items);
+        let failed: Vec<_> = (0..res.len()).filter(|&i| !res[i]).collect();
+        assert_eq!(failed, [7, 30]);
+
+        let neg_r = Signature::new(sigs[3].r().neg(), sigs[3].s().clone());
+        items[3].2 = &neg_r;
+        assert!(!verify_batch_each::<Sha256>(&items)[3]);
+    }
+}
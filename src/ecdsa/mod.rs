@@ -0,0 +1,690 @@
+//! ECDSA signatures over the `ec` groups.
+//!
+//! Signing uses deterministic nonces (RFC 6979) by default, so the signature
+//! of a message under a given key is reproducible and doesn't depend on the
+//! quality of a random number generator.
+
+use crate::{
+    ec::{Group, GroupParams, Point},
+    mpi::Mpi,
+    traits::Hasher,
+};
+use core::{cell::Cell, fmt, mem::size_of, ptr, slice};
+use cry_sys::bindings::{cry_ecdsa_ctx, cry_ecdsa_sig, cry_ecdsa_sign, cry_ecdsa_verify, size_t};
+use rand_core::{CryptoRng, RngCore};
+use std::panic::{self, AssertUnwindSafe};
+
+mod batch;
+mod recovery;
+mod rfc6979;
+
+pub use batch::{verify_batch, verify_batch_each, BatchItem};
+pub use recovery::{recover_verifying_key, RecoverableSignature};
+pub use rfc6979::Rfc6979;
+
+const _: () = assert!(size_of::<Context>() == size_of::<cry_ecdsa_ctx>());
+const _: () = assert!(size_of::<Signature>() == size_of::<cry_ecdsa_sig>());
+
+// Layout of the backend context.
+#[repr(C)]
+struct Context {
+    grp: GroupParams,
+    d: Mpi,
+    q: Point,
+}
+
+impl Context {
+    // The backend prototypes take a mutable context, the keys build a copy.
+    fn new(grp: &Group, d: &Mpi, q: &Point) -> Self {
+        Context {
+            grp: grp.params().clone(),
+            d: d.clone(),
+            q: q.clone(),
+        }
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut cry_ecdsa_ctx {
+        self as *mut Context as *mut cry_ecdsa_ctx
+    }
+}
+
+// Nonce source of a signature and whether it panicked.
+struct NonceState<'a> {
+    source: &'a mut dyn FnMut(&mut [u8]),
+    panicked: bool,
+}
+
+thread_local! {
+    // `NonceState` of the signature being computed by this thread. The backend
+    // callback takes no user data, so it's reachable from here only.
+    static NONCE_STATE: Cell<*mut ()> = const { Cell::new(ptr::null_mut()) };
+}
+
+// Restores the previous nonce state, also when unwinding.
+struct NonceGuard(*mut ());
+
+impl Drop for NonceGuard {
+    fn drop(&mut self) {
+        NONCE_STATE.with(|cell| cell.set(self.0));
+    }
+}
+
+unsafe extern "C" fn nonce_callback(buf: *mut u8, len: size_t) {
+    let buf = slice::from_raw_parts_mut(buf, len as usize);
+    // Only called by the backend within `sign_with_nonces`, which keeps the
+    // state alive and exclusively borrowed for the whole call.
+    let state = &mut *(NONCE_STATE.with(Cell::get) as *mut NonceState);
+    if !state.panicked {
+        let source = &mut state.source;
+        state.panicked = panic::catch_unwind(AssertUnwindSafe(|| source(buf))).is_err();
+    }
+    if state.panicked {
+        // Unwinding through the backend is not possible. A valid nonce lets the
+        // signature terminate, then it's discarded.
+        buf.fill(0);
+        if let Some(last) = buf.last_mut() {
+            *last = 1;
+        }
+    }
+}
+
+// Leftmost bits of the digest, as many as the order ones.
+fn digest_scalar(digest: &[u8], n: &Mpi) -> Mpi {
+    let e = Mpi::from_bytes_be(digest);
+    let (dlen, qlen) = (8 * digest.len(), n.bits_count());
+    if dlen > qlen {
+        e.shr(dlen - qlen)
+    } else {
+        e
+    }
+}
+
+// Big-endian `x` in exactly `buf.len()` bytes.
+fn write_be(x: &Mpi, buf: &mut [u8]) {
+    let bytes = x.to_bytes_be();
+    let skip = bytes.len().saturating_sub(buf.len());
+    let (pad, tail) = buf.split_at_mut(buf.len() - (bytes.len() - skip));
+    pad.fill(0);
+    tail.copy_from_slice(&bytes[skip..]);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SignatureComponents", into = "SignatureComponents")
+)]
+#[repr(C)]
+pub struct Signature {
+    r: Mpi,
+    s: Mpi,
+}
+
+impl Signature {
+    pub fn new(r: Mpi, s: Mpi) -> Self {
+        Signature { r, s }
+    }
+
+    pub fn r(&self) -> &Mpi {
+        &self.r
+    }
+
+    pub fn s(&self) -> &Mpi {
+        &self.s
+    }
+
+    /// Checks that `s` is at most half the group `order`.
+    pub fn is_low_s(&self, order: &Mpi) -> bool {
+        self.s <= order.shr(1)
+    }
+
+    /// Equivalent signature with `s` replaced by `order - s` when above half
+    /// the `order`, which removes the signature malleability.
+    pub fn normalize_s(self, order: &Mpi) -> Self {
+        if self.is_low_s(order) {
+            self
+        } else {
+            let s = order - &self.s;
+            Signature { r: self.r, s }
+        }
+    }
+
+    fn as_ptr(&self) -> *const cry_ecdsa_sig {
+        self as *const Signature as *const cry_ecdsa_sig
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut cry_ecdsa_sig {
+        self as *mut Signature as *mut cry_ecdsa_sig
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "SigningComponents", into = "SigningComponents")
+)]
+pub struct SigningKey {
+    group: Group,
+    d: Mpi,
+    q: Point,
+}
+
+impl SigningKey {
+    /// Key with secret scalar `d`, which must be in the range `[1, n)`.
+    pub fn new(group: Group, d: Mpi) -> Result<Self, String> {
+        if !group.is_valid_scalar(&d) {
+            return Err("Invalid private key".into());
+        }
+        let q = group.mul_generator(&d);
+        Ok(SigningKey { group, d, q })
+    }
+
+    pub fn random<R: RngCore + CryptoRng>(group: Group, rng: &mut R) -> Self {
+        let d = group.random_scalar(rng);
+        Self::new(group, d).expect("Scalar in range")
+    }
+
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    pub fn secret_scalar(&self) -> &Mpi {
+        &self.d
+    }
+
+    pub fn verifying_key(&self) -> VerifyingKey {
+        VerifyingKey {
+            group: self.group.clone(),
+            q: self.q.clone(),
+        }
+    }
+
+    /// Signs the `H` digest of `msg` with a deterministic nonce.
+    pub fn sign<H: Hasher>(&self, msg: impl AsRef<[u8]>) -> Result<Signature, String> {
+        self.sign_prehash::<H>(H::digest_parts(&[msg.as_ref()]))
+    }
+
+    /// Signs a message digest with a deterministic nonce derived with
+    /// HMAC-`H`, which should be the hash used for the digest.
+    pub fn sign_prehash<H: Hasher>(&self, digest: impl AsRef<[u8]>) -> Result<Signature, String> {
+        let digest = digest.as_ref();
+        let mut nonces = Rfc6979::<H>::new(&self.d, self.group.order(), digest);
+        self.sign_with_nonces(digest, &mut |buf| {
+            write_be(&nonces.next().expect("Endless sequence"), buf)
+        })
+    }
+
+    /// Signs the `H` digest of `msg` with a random nonce drawn from `rng`.
+    pub fn sign_with_rng<H: Hasher, R: RngCore + CryptoRng>(
+        &self,
+        msg: impl AsRef<[u8]>,
+        rng: &mut R,
+    ) -> Result<Signature, String> {
+        let digest = H::digest_parts(&[msg.as_ref()]);
+        self.sign_with_nonces(&digest, &mut |buf| rng.fill_bytes(buf))
+    }
+
+    fn sign_with_nonces(
+        &self,
+        digest: &[u8],
+        nonces: &mut dyn FnMut(&mut [u8]),
+    ) -> Result<Signature, String> {
+        let mut sig = Signature::new(Mpi::new(), Mpi::new());
+        let mut ctx = Context::new(&self.group, &self.d, &self.q);
+        let mut state = NonceState {
+            source: nonces,
+            panicked: false,
+        };
+        let res = {
+            let state = &mut state as *mut NonceState as *mut ();
+            let _guard = NonceGuard(NONCE_STATE.with(|cell| cell.replace(state)));
+            unsafe {
+                cry_ecdsa_sign(
+                    ctx.as_mut_ptr(),
+                    sig.as_mut_ptr(),
+                    digest.as_ptr(),
+                    digest.len() as size_t,
+                    Some(nonce_callback),
+                )
+            }
+        };
+        if state.panicked {
+            return Err("Nonce generation failure".into());
+        }
+        if res != 0 {
+            return Err("Signature generation failure".into());
+        }
+        Ok(sig)
+    }
+}
+
+// Only the public part, the secret scalar is not printed.
+impl fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("q", &self.q)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "VerifyingComponents", into = "VerifyingComponents")
+)]
+pub struct VerifyingKey {
+    group: Group,
+    q: Point,
+}
+
+impl VerifyingKey {
+    /// Key with public point `q`, which must be on the curve.
+    pub fn new(group: Group, q: Point) -> Result<Self, String> {
+        if !group.is_on_curve(&q) {
+            return Err("Invalid public key".into());
+        }
+        Ok(VerifyingKey { group, q })
+    }
+
+    /// Key from its SEC 1 encoding, compressed or not.
+    pub fn from_sec1(group: Group, bytes: &[u8]) -> Result<Self, String> {
+        let q = Point::from_sec1(&group, bytes)?;
+        Self::new(group, q)
+    }
+
+    pub fn to_sec1(&self, compressed: bool) -> Vec<u8> {
+        self.q.to_sec1(&self.group, compressed)
+    }
+
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    pub fn point(&self) -> &Point {
+        &self.q
+    }
+
+    /// Verifies a signature over the `H` digest of `msg`.
+    pub fn verify<H: Hasher>(&self, msg: impl AsRef<[u8]>, signature: &Signature) -> bool {
+        self.verify_prehash(H::digest_parts(&[msg.as_ref()]), signature)
+    }
+
+    /// Verifies a signature over a message digest.
+    ///
+    /// `u1 * G + u2 * Q` is computed with the group generator table and a
+    /// single chain of doublings for `Q`.
+    pub fn verify_prehash(&self, digest: impl AsRef<[u8]>, signature: &Signature) -> bool {
+        verify_digest(&self.group, &self.q, digest.as_ref(), signature)
+    }
+
+    /// Verifies a signature over a message digest with the backend, which
+    /// computes `u1 * G` and `u2 * Q` separately. It's slower than
+    /// `verify_prehash` and kept as a reference.
+    pub fn verify_prehash_backend(&self, digest: impl AsRef<[u8]>, signature: &Signature) -> bool {
+        let digest = digest.as_ref();
+        let n = self.group.order();
+        let in_range = |x: &Mpi| !x.is_zero() && !x.is_negative() && x < n;
+        if !in_range(signature.r()) || !in_range(signature.s()) {
+            return false;
+        }
+        let mut ctx = Context::new(&self.group, &Mpi::new(), &self.q);
+        let res = unsafe {
+            cry_ecdsa_verify(
+                ctx.as_mut_ptr(),
+                signature.as_ptr(),
+                digest.as_ptr(),
+                digest.len() as size_t,
+            )
+        };
+        res == 1
+    }
+}
+
+fn verify_digest(grp: &Group, q: &Point, digest: &[u8], signature: &Signature) -> bool {
+    let n = grp.order();
+    let (r, s) = (signature.r(), signature.s());
+    let in_range = |x: &Mpi| !x.is_zero() && !x.is_negative() && x < n;
+    if !in_range(r) || !in_range(s) {
+        return false;
+    }
+    let Some(w) = s.mod_inv(n) else {
+        return false;
+    };
+    let u1 = (&digest_scalar(digest, n) * &w) % n;
+    let u2 = (r * &w) % n;
+    let point = grp.generator_table().mul_add(&u1, &[(u2, q.clone())]);
+    !point.is_identity() && &(point.x() % n) == r
+}
+
+impl fmt::Debug for VerifyingKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifyingKey")
+            .field("q", &self.q)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for VerifyingKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.q == other.q && self.group.order() == other.group.order()
+    }
+}
+
+// Serialized forms, validated on deserialization.
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SignatureComponents {
+    r: Mpi,
+    s: Mpi,
+}
+
+#[cfg(feature = "serde")]
+impl From<Signature> for SignatureComponents {
+    fn from(sig: Signature) -> Self {
+        SignatureComponents { r: sig.r, s: sig.s }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SignatureComponents> for Signature {
+    type Error = String;
+
+    // The range upper bound depends on the group, checked by verification.
+    fn try_from(c: SignatureComponents) -> Result<Self, Self::Error> {
+        let positive = |x: &Mpi| !x.is_zero() && !x.is_negative();
+        if !positive(&c.r) || !positive(&c.s) {
+            return Err("Invalid signature".into());
+        }
+        Ok(Signature::new(c.r, c.s))
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SigningComponents {
+    group: Group,
+    d: Mpi,
+}
+
+#[cfg(feature = "serde")]
+impl From<SigningKey> for SigningComponents {
+    fn from(key: SigningKey) -> Self {
+        SigningComponents {
+            group: key.group,
+            d: key.d,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<SigningComponents> for SigningKey {
+    type Error = String;
+
+    fn try_from(c: SigningComponents) -> Result<Self, Self::Error> {
+        SigningKey::new(c.group, c.d)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct VerifyingComponents {
+    group: Group,
+    x: Mpi,
+    y: Mpi,
+}
+
+#[cfg(feature = "serde")]
+impl From<VerifyingKey> for VerifyingComponents {
+    fn from(key: VerifyingKey) -> Self {
+        VerifyingComponents {
+            group: key.group,
+            x: key.q.x().clone(),
+            y: key.q.y().clone(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<VerifyingComponents> for VerifyingKey {
+    type Error = String;
+
+    fn try_from(c: VerifyingComponents) -> Result<Self, Self::Error> {
+        VerifyingKey::new(c.group, Point::new(c.x, c.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ec::Curve,
+        sha1::Sha1,
+        sha256::Sha256,
+        sha512::{Sha384, Sha512},
+    };
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // RFC 6979, A.2.5, A.2.6 and A.2.7.
+    const P256_X: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+    const P256_UX: &str = "60fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6";
+    const P256_UY: &str = "7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299";
+    const P384_X: &str = "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d896d5724e4c70a825f872c9ea60d2edf5";
+    const P521_X: &str = "00fad06daa62ba3b25d2fb40133da757205de67f5bb0018fee8c86e1b68c7e75caa896eb32f1f47c70855836a6d16fcc1466f6d8fbec67db89ec0c08b0e996b83538";
+
+    fn key(curve: Curve, x: &str) -> SigningKey {
+        SigningKey::new(Group::new(curve), Mpi::from_hex(x).unwrap()).unwrap()
+    }
+
+    fn check<H: Hasher>(key: &SigningKey, msg: &str, r: &str, s: &str) {
+        let sig = key.sign::<H>(msg).unwrap();
+        let vk = key.verifying_key();
+
+        assert_eq!(sig.r(), &Mpi::from_hex(r).unwrap());
+        assert_eq!(sig.s(), &Mpi::from_hex(s).unwrap());
+        assert!(vk.verify::<H>(msg, &sig));
+        assert!(vk.verify_prehash_backend(H::digest_parts(&[msg.as_bytes()]), &sig));
+    }
+
+    #[test]
+    fn p256_vectors() {
+        let key = key(Curve::Secp256r1, P256_X);
+        let u = Point::new(
+            Mpi::from_hex(P256_UX).unwrap(),
+            Mpi::from_hex(P256_UY).unwrap(),
+        );
+        assert_eq!(key.verifying_key().point(), &u);
+
+        check::<Sha1>(
+            &key,
+            "sample",
+            "61340c88c3aaebeb4f6d667f672ca9759a6ccaa9fa8811313039ee4a35471d32",
+            "6d7f147dac089441bb2e2fe8f7a3fa264b9c475098fdcf6e00d7c996e1b8b7eb",
+        );
+        check::<Sha256>(
+            &key,
+            "sample",
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+            "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+        );
+        check::<Sha256>(
+            &key,
+            "test",
+            "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367",
+            "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083",
+        );
+    }
+
+    #[test]
+    fn p384_vectors() {
+        let key = key(Curve::Secp384r1, P384_X);
+
+        check::<Sha384>(
+            &key,
+            "sample",
+            "94edbb92a5ecb8aad4736e56c691916b3f88140666ce9fa73d64c4ea95ad133c81a648152e44acf96e36dd1e80fabe46",
+            "99ef4aeb15f178cea1fe40db2603138f130e740a19624526203b6351d0a3a94fa329c145786e679e7b82c71a38628ac8",
+        );
+        check::<Sha384>(
+            &key,
+            "test",
+            "8203b63d3c853e8d77227fb377bcf7b7b772e97892a80f36ab775d509d7a5feb0542a7f0812998da8f1dd3ca3cf023db",
+            "ddd0760448d42d8a43af45af836fce4de8be06b485e9b61b827c2f13173923e06a739f040649a667bf3b828246baa5a5",
+        );
+    }
+
+    #[test]
+    fn p521_vectors() {
+        let key = key(Curve::Secp521r1, P521_X);
+
+        check::<Sha512>(
+            &key,
+            "sample",
+            "00c328fafcbd79dd77850370c46325d987cb525569fb63c5d3bc53950e6d4c5f174e25a1ee9017b5d450606add152b534931d7d4e8455cc91f9b15bf05ec36e377fa",
+            "00617cce7cf5064806c467f678d3b4080d6f1cc50af26ca209417308281b68af282623eaa63e5b5c0723d8b8c37ff0777b1a20f8ccb1dccc43997f1ee0e44da4a67a",
+        );
+        check::<Sha512>(
+            &key,
+            "test",
+            "013e99020abf5cee7525d16b69b229652ab6bdf2affcaef38773b4b7d08725f10cdb93482fdcc54edcee91eca4166b2a7c6265ef0ce2bd7051b7cef945babd47ee6d",
+            "01fbd0013c674aa79cb39849527916ce301c66ea7ce8b80682786ad60f98f7e78a19ca69eff5c57400e3b3a0ad66ce0978214d13baf4e9ac60752f7b155e2de4dce3",
+        );
+    }
+
+    #[test]
+    fn deterministic() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let key = SigningKey::random(Group::new(Curve::Secp256k1), &mut rng);
+
+        let sig1 = key.sign::<Sha256>(b"audit").unwrap();
+        let sig2 = key.sign::<Sha256>(b"audit").unwrap();
+        let sig3 = key.sign_with_rng::<Sha256, _>(b"audit", &mut rng).unwrap();
+
+        assert_eq!(sig1, sig2);
+        assert_ne!(sig1, sig3);
+        assert!(key.verifying_key().verify::<Sha256>(b"audit", &sig3));
+    }
+
+    #[test]
+    fn nonce_panic() {
+        let key = key(Curve::Secp256r1, P256_X);
+        let digest = Sha256::digest_parts(&[b"sample"]);
+        let mut calls = 0;
+
+        let res = key.sign_with_nonces(&digest, &mut |_| {
+            calls += 1;
+            panic!("No nonce");
+        });
+        assert_eq!(res, Err("Nonce generation failure".into()));
+        assert_eq!(calls, 1);
+        assert!(NONCE_STATE.with(Cell::get).is_null());
+        assert!(key.sign::<Sha256>("sample").is_ok());
+    }
+
+    #[test]
+    fn custom_group() {
+        let named = key(Curve::Secp256r1, P256_X);
+        let grp = named.group();
+        let custom = Group::from_params(
+            grp.p().clone(),
+            grp.a().clone(),
+            grp.b().clone(),
+            grp.generator().clone(),
+            grp.order().clone(),
+            grp.cofactor(),
+        )
+        .unwrap();
+        let key = SigningKey::new(custom, Mpi::from_hex(P256_X).unwrap()).unwrap();
+
+        let sig = key.sign::<Sha256>("sample").unwrap();
+        assert_eq!(sig, named.sign::<Sha256>("sample").unwrap());
+        assert!(named.verifying_key().verify::<Sha256>("sample", &sig));
+        assert!(key.verifying_key().verify::<Sha256>("sample", &sig));
+    }
+
+    #[test]
+    fn verify_rejects() {
+        let key = key(Curve::Secp256r1, P256_X);
+        let vk = key.verifying_key();
+        let sig = key.sign::<Sha256>("sample").unwrap();
+        let n = key.group().order();
+
+        assert!(!vk.verify::<Sha256>("other", &sig));
+        let digest = Sha256::digest_parts(&[b"other"]);
+        assert!(!vk.verify_prehash_backend(digest, &sig));
+        assert!(!vk.verify::<Sha384>("sample", &sig));
+        let bad_s = Signature::new(sig.r().clone(), sig.s() + &Mpi::from(1i64));
+        assert!(!vk.verify::<Sha256>("sample", &bad_s));
+        let high_r = Signature::new(sig.r() + n, sig.s().clone());
+        assert!(!vk.verify::<Sha256>("sample", &high_r));
+        assert!(!vk.verify::<Sha256>("sample", &Signature::new(Mpi::new(), Mpi::new())));
+    }
+
+    #[test]
+    fn sec1_keys() {
+        let vk = key(Curve::Secp256r1, P256_X).verifying_key();
+        let compressed = vk.to_sec1(true);
+
+        assert_eq!(hex::encode(&compressed[1..]), P256_UX);
+        assert_eq!(compressed.len(), 33);
+        let decoded = VerifyingKey::from_sec1(Group::new(Curve::Secp256r1), &compressed).unwrap();
+        assert_eq!(decoded, vk);
+        let decoded = VerifyingKey::from_sec1(vk.group().clone(), &vk.to_sec1(false)).unwrap();
+        assert_eq!(decoded, vk);
+        assert!(VerifyingKey::from_sec1(Group::new(Curve::Secp384r1), &compressed).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let key = key(Curve::Secp256r1, P256_X);
+        let vk = key.verifying_key();
+        let sig = key.sign::<Sha256>("sample").unwrap();
+
+        let json = serde_json::to_string(&key).unwrap();
+        let res: SigningKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(res.secret_scalar(), key.secret_scalar());
+        assert_eq!(res.verifying_key(), vk);
+
+        let json = serde_json::to_string(&vk).unwrap();
+        let res: VerifyingKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(res, vk);
+        assert!(res.verify::<Sha256>("sample", &sig));
+
+        let cbor = serde_cbor::to_vec(&sig).unwrap();
+        assert_eq!(serde_cbor::from_slice::<Signature>(&cbor).unwrap(), sig);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_rejects() {
+        let key = key(Curve::Secp256r1, P256_X);
+        let mut json: serde_json::Value = serde_json::to_value(key.verifying_key()).unwrap();
+        json["y"] = "1".into();
+        assert!(serde_json::from_value::<VerifyingKey>(json.clone()).is_err());
+        json["group"]["h"] = "2".into();
+        assert!(serde_json::from_value::<VerifyingKey>(json).is_err());
+
+        let mut json: serde_json::Value = serde_json::to_value(&key).unwrap();
+        json["d"] = json["group"]["n"].clone();
+        assert!(serde_json::from_value::<SigningKey>(json).is_err());
+
+        assert!(serde_json::from_str::<Signature>(r#"{"r":"0","s":"1"}"#).is_err());
+        assert!(serde_json::from_str::<Signature>(r#"{"r":"1","s":"-1"}"#).is_err());
+        assert!(serde_json::from_str::<Signature>(r#"{"r":"1","s":"1"}"#).is_ok());
+    }
+
+    #[test]
+    fn invalid_keys() {
+        let grp = Group::new(Curve::Secp256r1);
+
+        assert!(SigningKey::new(grp.clone(), Mpi::new()).is_err());
+        assert!(SigningKey::new(grp.clone(), grp.order().clone()).is_err());
+        let off_curve = Point::new(Mpi::from(1i64), Mpi::from(1i64));
+        assert!(VerifyingKey::new(grp.clone(), off_curve).is_err());
+        assert!(VerifyingKey::new(grp.clone(), Point::identity()).is_err());
+        assert!(VerifyingKey::new(grp.clone(), grp.generator().clone()).is_ok());
+    }
+}
@@ -0,0 +1,325 @@
+use super::{digest_scalar, Signature, SigningKey, VerifyingKey};
+use crate::{
+    ec::{Curve, Group, Point},
+    mpi::Mpi,
+    traits::Hasher,
+};
+
+/// Signature with the recovery id `v`, which selects the nonce point among
+/// the candidates sharing the `r` coordinate and allows to recover the
+/// signer's public key.
+///
+/// Bit 0 of `v` is the parity of the point `y` coordinate and bit 1 is set
+/// when its `x` coordinate exceeds the group order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecoverableSignature {
+    sig: Signature,
+    v: u8,
+}
+
+// Length of the secp256k1 scalars in the compact encoding.
+const SCALAR_LEN: usize = 32;
+// Offset added to the recovery id in the compact encoding.
+const V_OFFSET: u8 = 27;
+
+impl RecoverableSignature {
+    pub fn new(sig: Signature, v: u8) -> Result<Self, String> {
+        if v > 3 {
+            return Err("Invalid recovery id".into());
+        }
+        Ok(RecoverableSignature { sig, v })
+    }
+
+    pub fn signature(&self) -> &Signature {
+        &self.sig
+    }
+
+    pub fn recovery_id(&self) -> u8 {
+        self.v
+    }
+
+    /// 65 bytes compact encoding `r || s || v` of a secp256k1 signature, with
+    /// the recovery id offset by 27 as in Ethereum.
+    pub fn to_bytes(&self) -> Result<[u8; 65], String> {
+        let mut out = [0; 65];
+        for (x, chunk) in [&self.sig.r, &self.sig.s]
+            .into_iter()
+            .zip(out.chunks_mut(SCALAR_LEN))
+        {
+            let bytes = x.to_bytes_be();
+            if bytes.len() > SCALAR_LEN {
+                return Err("Scalar too large".into());
+            }
+            chunk[SCALAR_LEN - bytes.len()..].copy_from_slice(&bytes);
+        }
+        out[64] = V_OFFSET + self.v;
+        Ok(out)
+    }
+
+    /// Decodes the compact encoding, accepting the recovery id both with and
+    /// without the 27 offset.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != 65 {
+            return Err("Invalid signature length".into());
+        }
+        let v = match bytes[64] {
+            v @ 0..=3 => v,
+            v @ 27..=30 => v - V_OFFSET,
+            _ => return Err("Invalid recovery id".into()),
+        };
+        let r = Mpi::from_bytes_be(&bytes[..SCALAR_LEN]);
+        let s = Mpi::from_bytes_be(&bytes[SCALAR_LEN..2 * SCALAR_LEN]);
+        Self::new(Signature::new(r, s), v)
+    }
+}
+
+impl SigningKey {
+    /// Recoverable signature of the `H` digest of `msg`, with a
+    /// deterministic nonce and a low `s`.
+    pub fn sign_recoverable<H: Hasher>(
+        &self,
+        msg: impl AsRef<[u8]>,
+    ) -> Result<RecoverableSignature, String> {
+        self.sign_prehash_recoverable::<H>(H::digest_parts(&[msg.as_ref()]))
+    }
+
+    /// Recoverable signature of a message digest, see `sign_prehash`.
+    pub fn sign_prehash_recoverable<H: Hasher>(
+        &self,
+        digest: impl AsRef<[u8]>,
+    ) -> Result<RecoverableSignature, String> {
+        let digest = digest.as_ref();
+        let grp = self.group();
+        let n = grp.order();
+        let sig = self.sign_prehash::<H>(digest)?;
+        // The backend doesn't return the nonce point, R = u1 * G + u2 * Q is
+        // computed again as in the verification.
+        let w = sig.s().mod_inv(n).ok_or("Signature generation failure")?;
+        let u1 = (&digest_scalar(digest, n) * &w) % n;
+        let u2 = (sig.r() * &w) % n;
+        let big_r = grp.generator_table().mul_add(&u1, &[(u2, self.q.clone())]);
+        if big_r.is_identity() || &(big_r.x() % n) != sig.r() {
+            return Err("Signature generation failure".into());
+        }
+        let mut v = u8::from(big_r.y().is_odd()) | u8::from(big_r.x() >= n) << 1;
+        // A high s is negated along with the nonce, so R becomes -R.
+        if !sig.is_low_s(n) {
+            v ^= 1;
+        }
+        Ok(RecoverableSignature {
+            sig: sig.normalize_s(n),
+            v,
+        })
+    }
+}
+
+impl VerifyingKey {
+    /// Public key which produced `sig` over the message digest.
+    pub fn recover_from_prehash(
+        group: &Group,
+        digest: impl AsRef<[u8]>,
+        sig: &RecoverableSignature,
+    ) -> Result<Self, String> {
+        let q = recover(group, digest.as_ref(), &sig.sig, sig.v)?;
+        VerifyingKey::new(group.clone(), q)
+    }
+}
+
+/// Recovers the secp256k1 public key which produced `sig` over the message
+/// digest `msg_hash`. The digest is taken as is, so any hash (e.g. Keccak)
+/// can be used.
+pub fn recover_verifying_key(
+    msg_hash: impl AsRef<[u8]>,
+    sig: &RecoverableSignature,
+) -> Result<VerifyingKey, String> {
+    VerifyingKey::recover_from_prehash(&Group::new(Curve::Secp256k1), msg_hash, sig)
+}
+
+// Q = r^-1 * (s * R - e * G) (SEC 1, 4.1.6)
+fn recover(grp: &Group, digest: &[u8], sig: &Signature, v: u8) -> Result<Point, String> {
+    let (n, p) = (grp.order(), grp.p());
+    let (r, s) = (sig.r(), sig.s());
+    let in_range = |x: &Mpi| !x.is_zero() && !x.is_negative() && x < n;
+    if !in_range(r) || !in_range(s) {
+        return Err("Invalid signature".into());
+    }
+    let x = if v & 2 != 0 { r + n } else { r.clone() };
+    if &x >= p {
+        return Err("Invalid recovery id".into());
+    }
+    let alpha = (&(&(&(&x * &x) * &x) + &(grp.a() * &x)) + grp.b()).rem_euclid(p);
+    let beta = alpha.mod_sqrt(p).ok_or("Invalid signature")?;
+    let y = if beta.is_odd() == (v & 1 != 0) {
+        beta
+    } else {
+        p - &beta
+    };
+    let big_r = Point::new(x, y);
+
+    let e = digest_scalar(digest, n);
+    let r_inv = r.mod_inv(n).ok_or("Invalid signature")?;
+    let u1 = (&e * &r_inv).neg().rem_euclid(n);
+    let u2 = (s * &r_inv) % n;
+    let q = grp.add(&grp.mul_generator(&u1), &grp.mul(&u2, &big_r));
+    if q.is_identity() {
+        return Err("Invalid signature".into());
+    }
+    Ok(q)
+}
+
+/// The 65 bytes compact encoding, see `to_bytes`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for RecoverableSignature {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let bytes = self.to_bytes().map_err(serde::ser::Error::custom)?;
+        crate::serde::bytes::serialize(&bytes, s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for RecoverableSignature {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let bytes: Vec<u8> = crate::serde::bytes::deserialize(d)?;
+        Self::from_bytes(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sha256::Sha256;
+
+    // Produced by OpenSSL with deterministic nonces, s normalized.
+    const VECTORS: [(&str, &[u8], &str, &str); 2] = [
+        (
+            "4646464646464646464646464646464646464646464646464646464646464646",
+            b"indexer",
+            "4bc2a31265153f07e70e0bab08724e6b85e217f8cd628ceb62974247bb493382ce28cab79ad7119ee1ad3ebcdb98a16805211530ecc6cfefa1b88e6dff99232a",
+            "ca09f4beb275ee492185507b538dc991f2a34b6d9b934d1c84d6939a0eb7ab230579b27e9e8da06369f725b274fd147428d36c05b049e01cd3b658c9082686151b",
+        ),
+        (
+            "c85ef7d79691fe79573b1a7064c19c1a9819ebdbd1faaab1a8ec92344438aaf4",
+            b"transfer 1 ETH",
+            "0947751e3022ecf3016be03ec77ab0ce3c2662b4843898cb068d74f698ccc8ad75aa17564ae80a20bb044ee7a6d903e8e8df624b089c95d66a0570f051e5a05b",
+            "bb8c85aab4116280a95038db4fdf7a1b5f66434a4107a6e86b668f7d6357156665457928f2bd904a0f2a177d1b4161dfe0966b16498a8ea1fb8e3dcda89e1e6a1c",
+        ),
+    ];
+
+    fn point(hex: &str) -> Point {
+        let (x, y) = hex.split_at(64);
+        Point::new(Mpi::from_hex(x).unwrap(), Mpi::from_hex(y).unwrap())
+    }
+
+    #[test]
+    fn sign_vectors() {
+        for (d, msg, q, sig) in VECTORS {
+            let key =
+                SigningKey::new(Group::new(Curve::Secp256k1), Mpi::from_hex(d).unwrap()).unwrap();
+
+            let rsig = key.sign_recoverable::<Sha256>(msg).unwrap();
+
+            assert_eq!(key.verifying_key().point(), &point(q));
+            assert_eq!(hex::encode(rsig.to_bytes().unwrap()), sig);
+            assert!(rsig.signature().is_low_s(key.group().order()));
+            assert!(key.verifying_key().verify::<Sha256>(msg, rsig.signature()));
+        }
+    }
+
+    #[test]
+    fn recovery_ids() {
+        let key = SigningKey::new(Group::new(Curve::Secp256k1), Mpi::from(1234u64)).unwrap();
+        let mut parities = [false; 2];
+        for i in 0..16 {
+            let digest = Sha256::digest_parts(&[format!("message {i}").as_bytes()]);
+
+            let rsig = key.sign_prehash_recoverable::<Sha256>(&digest).unwrap();
+
+            let recovered = VerifyingKey::recover_from_prehash(key.group(), &digest, &rsig);
+            assert_eq!(recovered.unwrap(), key.verifying_key());
+            parities[usize::from(rsig.recovery_id() & 1)] = true;
+        }
+        assert_eq!(parities, [true, true]);
+    }
+
+    #[test]
+    fn recover_vectors() {
+        for (_, msg, q, sig) in VECTORS {
+            let sig = RecoverableSignature::from_bytes(&hex::decode(sig).unwrap()).unwrap();
+            let digest = Sha256::digest_parts(&[msg]);
+
+            let key = recover_verifying_key(&digest, &sig).unwrap();
+
+            assert_eq!(key.point(), &point(q));
+            let other = recover_verifying_key(Sha256::digest_parts(&[b"other"]), &sig).unwrap();
+            assert_ne!(other.point(), &point(q));
+        }
+    }
+
+    #[test]
+    fn compact_encoding() {
+        let (_, _, _, sig) = VECTORS[1];
+        let mut bytes = hex::decode(sig).unwrap();
+
+        let rsig = RecoverableSignature::from_bytes(&bytes).unwrap();
+        assert_eq!(rsig.recovery_id(), 1);
+        bytes[64] = 1;
+        assert_eq!(RecoverableSignature::from_bytes(&bytes).unwrap(), rsig);
+        bytes[64] = 4;
+        assert!(RecoverableSignature::from_bytes(&bytes).is_err());
+        assert!(RecoverableSignature::from_bytes(&bytes[1..]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_compact() {
+        let (_, _, _, sig) = VECTORS[0];
+        let rsig = RecoverableSignature::from_bytes(&hex::decode(sig).unwrap()).unwrap();
+
+        let json = serde_json::to_string(&rsig).unwrap();
+        assert_eq!(json, format!("\"{sig}\""));
+        assert_eq!(
+            serde_json::from_str::<RecoverableSignature>(&json).unwrap(),
+            rsig
+        );
+        let cbor = serde_cbor::to_vec(&rsig).unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<RecoverableSignature>(&cbor).unwrap(),
+            rsig
+        );
+
+        let short = format!("\"{}\"", &sig[2..]);
+        assert!(serde_json::from_str::<RecoverableSignature>(&short).is_err());
+        let bad_v = format!("\"{}ff\"", &sig[..128]);
+        assert!(serde_json::from_str::<RecoverableSignature>(&bad_v).is_err());
+    }
+
+    #[test]
+    fn recover_rejects() {
+        let (_, msg, _, sig) = VECTORS[0];
+        let digest = Sha256::digest_parts(&[msg]);
+        let sig = RecoverableSignature::from_bytes(&hex::decode(sig).unwrap()).unwrap();
+        let n = Group::new(Curve::Secp256k1).order().clone();
+
+        let zero_r = RecoverableSignature::new(Signature::new(Mpi::new(), sig.sig.s.clone()), 0);
+        assert!(recover_verifying_key(&digest, &zero_r.unwrap()).is_err());
+        let high_s = RecoverableSignature::new(Signature::new(sig.sig.r.clone(), n), 0);
+        assert!(recover_verifying_key(&digest, &high_s.unwrap()).is_err());
+        // r + n exceeds the field prime
+        let overflow = RecoverableSignature::new(sig.sig.clone(), sig.v | 2).unwrap();
+        assert!(recover_verifying_key(&digest, &overflow).is_err());
+    }
+
+    #[test]
+    fn low_s() {
+        let grp = Group::new(Curve::Secp256k1);
+        let n = grp.order();
+        let half = n.shr(1);
+        let sig = Signature::new(Mpi::from(1i64), &half + &Mpi::from(1i64));
+
+        assert!(!sig.is_low_s(n));
+        let low = sig.normalize_s(n);
+        assert!(low.is_low_s(n));
+        assert_eq!(low.s(), &half);
+        assert_eq!(low.clone().normalize_s(n), low);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{hmac::Hmac, mpi::Mpi, traits::Hasher};
+use core::marker::PhantomData;
+use typenum::Unsigned;
+
+/// Deterministic nonce generator (RFC 6979, 3.2) built on HMAC-`H`.
+///
+/// Yields the sequence of nonce candidates in `[1, q)` for the private key
+/// `x` and the message digest `h1`. The first one is the nonce; the following
+/// ones are only used if the signature has to be restarted.
+pub struct Rfc6979<H: Hasher> {
+    q: Mpi,
+    k: Vec<u8>,
+    v: Vec<u8>,
+    started: bool,
+    _hasher: PhantomData<fn() -> H>,
+}
+
+impl<H: Hasher> Rfc6979<H> {
+    pub fn new(x: &Mpi, q: &Mpi, h1: &[u8]) -> Self {
+        let h_len = H::DigestLen::USIZE;
+        let mut gen = Rfc6979 {
+            q: q.clone(),
+            k: vec![0x00; h_len],
+            v: vec![0x01; h_len],
+            started: false,
+            _hasher: PhantomData,
+        };
+        let x = gen.int2octets(x);
+        let h1 = gen.bits2octets(h1);
+        for sep in [0x00, 0x01] {
+            gen.k = hmac::<H>(&gen.k, &[&gen.v, &[sep], &x, &h1]);
+            gen.v = hmac::<H>(&gen.k, &[&gen.v]);
+        }
+        gen
+    }
+
+    fn qlen(&self) -> usize {
+        self.q.bits_count()
+    }
+
+    fn bits2int(&self, bits: &[u8]) -> Mpi {
+        let x = Mpi::from_bytes_be(bits);
+        let blen = 8 * bits.len();
+        if blen > self.qlen() {
+            x.shr(blen - self.qlen())
+        } else {
+            x
+        }
+    }
+
+    fn int2octets(&self, x: &Mpi) -> Vec<u8> {
+        let rlen = self.qlen().div_ceil(8);
+        let bytes = x.to_bytes_be();
+        let mut out = vec![0; rlen.saturating_sub(bytes.len())];
+        out.extend_from_slice(&bytes[bytes.len().saturating_sub(rlen)..]);
+        out
+    }
+
+    fn bits2octets(&self, bits: &[u8]) -> Vec<u8> {
+        let z1 = self.bits2int(bits);
+        let z2 = if z1 >= self.q { &z1 - &self.q } else { z1 };
+        self.int2octets(&z2)
+    }
+}
+
+impl<H: Hasher> Iterator for Rfc6979<H> {
+    type Item = Mpi;
+
+    fn next(&mut self) -> Option<Mpi> {
+        loop {
+            if self.started {
+                self.k = hmac::<H>(&self.k, &[&self.v, &[0x00]]);
+                self.v = hmac::<H>(&self.k, &[&self.v]);
+            }
+            self.started = true;
+            let mut t = Vec::new();
+            while 8 * t.len() < self.qlen() {
+                self.v = hmac::<H>(&self.k, &[&self.v]);
+                t.extend_from_slice(&self.v);
+            }
+            let k = self.bits2int(&t);
+            if !k.is_zero() && k < self.q {
+                return Some(k);
+            }
+        }
+    }
+}
+
+fn hmac<H: Hasher>(key: &[u8], parts: &[&[u8]]) -> Vec<u8> {
+    let mut mac = Hmac::<H>::new(key);
+    for part in parts {
+        mac.update(part);
+    }
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sha1::Sha1,
+        sha256::Sha256,
+        sha512::{Sha384, Sha512},
+    };
+
+    // RFC 6979, A.2.5, A.2.6 and A.2.7.
+    const P256_Q: &str = "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551";
+    const P256_X: &str = "c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721";
+    const P384_Q: &str = "ffffffffffffffffffffffffffffffffffffffffffffffffc7634d81f4372ddf581a0db248b0a77aecec196accc52973";
+    const P384_X: &str = "6b9d3dad2e1b8c1c05b19875b6659f4de23c3b667bf297ba9aa47740787137d896d5724e4c70a825f872c9ea60d2edf5";
+    const P521_Q: &str = "01fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffa51868783bf2f966b7fcc0148f709a5d03bb5c9b8899c47aebb6fb71e91386409";
+    const P521_X: &str = "00fad06daa62ba3b25d2fb40133da757205de67f5bb0018fee8c86e1b68c7e75caa896eb32f1f47c70855836a6d16fcc1466f6d8fbec67db89ec0c08b0e996b83538";
+
+    fn nonce<H: Hasher>(q: &str, x: &str, msg: &str) -> Mpi {
+        let (q, x) = (Mpi::from_hex(q).unwrap(), Mpi::from_hex(x).unwrap());
+        let h1 = H::digest_parts(&[msg.as_bytes()]);
+        Rfc6979::<H>::new(&x, &q, &h1).next().unwrap()
+    }
+
+    fn check<H: Hasher>(q: &str, x: &str, msg: &str, k: &str) {
+        assert_eq!(nonce::<H>(q, x, msg), Mpi::from_hex(k).unwrap());
+    }
+
+    #[test]
+    fn p256_vectors() {
+        check::<Sha1>(
+            P256_Q,
+            P256_X,
+            "sample",
+            "882905f1227fd620fbf2abf21244f0ba83d0dc3a9103dbbee43a1fb858109db4",
+        );
+        check::<Sha256>(
+            P256_Q,
+            P256_X,
+            "sample",
+            "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60",
+        );
+        check::<Sha256>(
+            P256_Q,
+            P256_X,
+            "test",
+            "d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0",
+        );
+    }
+
+    #[test]
+    fn p384_vectors() {
+        check::<Sha384>(
+            P384_Q,
+            P384_X,
+            "sample",
+            "94ed910d1a099dad3254e9242ae85abde4ba15168eaf0ca87a555fd56d10fbca2907e3e83ba95368623b8c4686915cf9",
+        );
+        check::<Sha384>(
+            P384_Q,
+            P384_X,
+            "test",
+            "015ee46a5bf88773ed9123a5ab0807962d193719503c527b031b4c2d225092ada71f4a459bc0da98adb95837db8312ea",
+        );
+    }
+
+    #[test]
+    fn p521_vectors() {
+        check::<Sha1>(
+            P521_Q,
+            P521_X,
+            "sample",
+            "0089c071b419e1c2820962321787258469511958e80582e95d8378e0c2ccdb3cb42bede42f50e3fa3c71f5a76724281d31d9c89f0f91fc1be4918db1c03a5838d0f9",
+        );
+        check::<Sha512>(
+            P521_Q,
+            P521_X,
+            "sample",
+            "01dae2ea071f8110dc26882d4d5eae0621a3256fc8847fb9022e2b7d28e6f10198b1574fdd03a9053c08a1854a168aa5a57470ec97dd5ce090124ef52a2f7ecbffd3",
+        );
+        check::<Sha512>(
+            P521_Q,
+            P521_X,
+            "test",
+            "016200813020ec986863bedfc1b121f605c1215645018aea1a7b215a564de9eb1b38a67aa1128b80ce391c4fb71187654aaa3431027bfc7f395766ca988c964dc56d",
+        );
+    }
+
+    #[test]
+    fn candidates() {
+        let q = Mpi::from_hex(P256_Q).unwrap();
+        let x = Mpi::from_hex(P256_X).unwrap();
+        let h1 = Sha256::digest_parts(&[b"sample"]);
+
+        let nonces: Vec<_> = Rfc6979::<Sha256>::new(&x, &q, &h1).take(3).collect();
+
+        assert_ne!(nonces[0], nonces[1]);
+        assert_ne!(nonces[1], nonces[2]);
+        assert!(nonces.iter().all(|k| !k.is_zero() && k < &q));
+    }
+}
@@ -1,17 +1,49 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
 // Utilities
+#[cfg(feature = "std")]
 pub mod base64;
+#[cfg(feature = "serde")]
+pub mod serde;
+pub mod uint;
 
 // Modern strong primitives
+#[cfg(feature = "std")]
 pub mod aes;
+#[cfg(feature = "std")]
 pub mod arc4;
+#[cfg(feature = "std")]
 pub mod cmac;
+#[cfg(feature = "std")]
 pub mod des;
+#[cfg(feature = "std")]
+pub mod ec;
+#[cfg(feature = "std")]
+pub mod ecdh;
+#[cfg(feature = "std")]
+pub mod ecdsa;
+#[cfg(feature = "std")]
 pub mod gcm;
+#[cfg(feature = "std")]
 pub mod hmac;
+#[cfg(feature = "std")]
 pub mod mpi;
+#[cfg(feature = "std")]
+pub mod paillier;
+#[cfg(feature = "std")]
+pub mod rsa;
+#[cfg(feature = "std")]
+pub mod schnorr;
+#[cfg(feature = "std")]
 pub mod sha1;
+#[cfg(feature = "std")]
 pub mod sha256;
+#[cfg(feature = "std")]
 pub mod sha512;
+#[cfg(feature = "std")]
+pub mod sss;
+#[cfg(feature = "std")]
+pub mod zkp;
 
 // Modern weak primitives
 #[cfg(feature = "weak")]
@@ -23,4 +55,10 @@ pub mod affine;
 #[cfg(feature = "historical")]
 pub mod hill;
 
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
 mod traits;
+
+#[cfg(feature = "std")]
+pub use traits::Hasher;
@@ -0,0 +1,224 @@
+use super::Mpi;
+use core::ops::{BitAnd, BitOr, BitXor, Shl, ShlAssign, Shr, ShrAssign};
+use cry_sys::bindings::{cry_mpi_digit, cry_mpi_shl, cry_mpi_shr};
+
+const DIGIT_BITS: usize = cry_sys::bindings::CRY_MPI_DIGIT_BITS as usize;
+
+// Shift and bit-level accessors operate on the absolute value, the sign is
+// preserved. Bitwise logic operators are defined only for non-negative values.
+impl Mpi {
+    pub fn shl(&self, n: usize) -> Self {
+        let mut res = Mpi::new();
+        checked!(cry_mpi_shl(&mut res.backend, &self.backend, n as u64));
+        res
+    }
+
+    pub fn shr(&self, n: usize) -> Self {
+        let mut res = Mpi::new();
+        checked!(cry_mpi_shr(&mut res.backend, &self.backend, n as u64));
+        res
+    }
+
+    pub fn shl_assign(&mut self, n: usize) {
+        checked!(cry_mpi_shl(&mut self.backend, &self.backend, n as u64));
+    }
+
+    pub fn shr_assign(&mut self, n: usize) {
+        checked!(cry_mpi_shr(&mut self.backend, &self.backend, n as u64));
+    }
+
+    /// Value of the `i`-th bit.
+    pub fn bit(&self, i: usize) -> bool {
+        self.digits()
+            .get(i / DIGIT_BITS)
+            .is_some_and(|d| (d >> (i % DIGIT_BITS)) & 1 == 1)
+    }
+
+    pub fn set_bit(&mut self, i: usize) {
+        if !self.bit(i) {
+            self.toggle_bit(i);
+        }
+    }
+
+    pub fn clear_bit(&mut self, i: usize) {
+        if self.bit(i) {
+            self.toggle_bit(i);
+        }
+    }
+
+    fn toggle_bit(&mut self, i: usize) {
+        let mut val = Mpi::from_int(1).shl(i);
+        if self.bit(i) != self.is_negative() {
+            val = val.neg();
+        }
+        self.add_assign(&val);
+    }
+
+    /// Number of trailing zero bits. Returns 0 for zero.
+    pub fn trailing_zeros(&self) -> usize {
+        let digits = self.digits();
+        digits
+            .iter()
+            .position(|&d| d != 0)
+            .map_or(0, |i| DIGIT_BITS * i + digits[i].trailing_zeros() as usize)
+    }
+
+    fn bitwise(
+        &self,
+        other: &Self,
+        op: impl Fn(cry_mpi_digit, cry_mpi_digit) -> cry_mpi_digit,
+    ) -> Self {
+        assert!(
+            !self.is_negative() && !other.is_negative(),
+            "Bitwise operations require non-negative operands"
+        );
+        let (a, b) = (self.digits(), other.digits());
+        let bytes: Vec<u8> = (0..a.len().max(b.len()))
+            .rev()
+            .flat_map(|i| {
+                let d = op(
+                    a.get(i).copied().unwrap_or(0),
+                    b.get(i).copied().unwrap_or(0),
+                );
+                d.to_be_bytes()
+            })
+            .collect();
+        Mpi::from_bytes_be(&bytes)
+    }
+
+    pub fn bitand(&self, other: &Self) -> Self {
+        self.bitwise(other, |a, b| a & b)
+    }
+
+    pub fn bitor(&self, other: &Self) -> Self {
+        self.bitwise(other, |a, b| a | b)
+    }
+
+    pub fn bitxor(&self, other: &Self) -> Self {
+        self.bitwise(other, |a, b| a ^ b)
+    }
+}
+
+impl Shl<usize> for Mpi {
+    type Output = Mpi;
+
+    fn shl(self, rhs: usize) -> Self::Output {
+        Self::shl(&self, rhs)
+    }
+}
+
+impl Shr<usize> for Mpi {
+    type Output = Mpi;
+
+    fn shr(self, rhs: usize) -> Self::Output {
+        Self::shr(&self, rhs)
+    }
+}
+
+impl ShlAssign<usize> for Mpi {
+    fn shl_assign(&mut self, rhs: usize) {
+        Self::shl_assign(self, rhs);
+    }
+}
+
+impl ShrAssign<usize> for Mpi {
+    fn shr_assign(&mut self, rhs: usize) {
+        Self::shr_assign(self, rhs);
+    }
+}
+
+impl BitAnd for Mpi {
+    type Output = Mpi;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        Self::bitand(&self, &rhs)
+    }
+}
+
+impl BitOr for Mpi {
+    type Output = Mpi;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self::bitor(&self, &rhs)
+    }
+}
+
+impl BitXor for Mpi {
+    type Output = Mpi;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        Self::bitxor(&self, &rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A_HEX: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68e1f9";
+    const B_HEX: &str = "6ba218432ef91f921a3dfe1b19725e61274cf66f76b5297c6ccd95c0d3d20ac1";
+
+    const SHL_RES: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68e1f90000";
+    const SHR_RES: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68";
+    const AND_RES: &str = "6822184326811e820225941a08520c41210c540976102864240505c0c04000c1";
+    const OR_RES: &str = "ebb2fcffaef9dfd7ba3fffbb9f72dff5efefff7f7ff5fdfc7ccf97effffaebf9";
+    const XOR_RES: &str = "8390e4bc8878c155b81a6ba19720d3b4cee3ab7609e5d59858ca922f3fbaeb38";
+
+    fn from_hex(s: &str) -> Mpi {
+        Mpi::from_hex(s).unwrap()
+    }
+
+    #[test]
+    fn shl() {
+        let a = from_hex(A_HEX);
+
+        let c = a << 16;
+
+        assert_eq!(format!("{:x}", c), SHL_RES);
+    }
+
+    #[test]
+    fn shr() {
+        let mut a = from_hex(A_HEX);
+
+        a >>= 16;
+
+        assert_eq!(format!("{:x}", a), SHR_RES);
+    }
+
+    #[test]
+    fn bits() {
+        let mut a = from_hex("10");
+
+        assert!(a.bit(4));
+        assert!(!a.bit(0));
+        assert!(!a.bit(1000));
+        assert_eq!(a.trailing_zeros(), 4);
+
+        a.set_bit(130);
+        a.clear_bit(4);
+
+        assert!(a.bit(130));
+        assert!(!a.bit(4));
+        assert_eq!(a.trailing_zeros(), 130);
+        assert_eq!(a.bits_count(), 131);
+    }
+
+    #[test]
+    fn bitwise() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        assert_eq!(format!("{:x}", a.clone() & b.clone()), AND_RES);
+        assert_eq!(format!("{:x}", a.clone() | b.clone()), OR_RES);
+        assert_eq!(format!("{:x}", a ^ b), XOR_RES);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bitwise_negative() {
+        let a = from_hex(A_HEX);
+
+        let _ = a.neg() & from_hex(B_HEX);
+    }
+}
@@ -0,0 +1,239 @@
+use super::Mpi;
+use core::{
+    fmt::{self, Binary, Display, Formatter, LowerHex, Octal, UpperHex},
+    mem::MaybeUninit,
+    str::FromStr,
+};
+use cry_sys::bindings::{cry_mpi_init_str, cry_mpi_store_str};
+
+impl Mpi {
+    /// Parses a number in the given radix, with an optional leading sign.
+    ///
+    /// Supported radixes are in the range `[2, 36]`, digits above 9 are
+    /// letters of either case.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, String> {
+        if !(2..=36).contains(&radix) {
+            return Err(format!("Unsupported radix {}", radix));
+        }
+        let (negative, digits) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if digits.is_empty() || !digits.chars().all(|c| c.is_digit(radix)) {
+            return Err(format!("Invalid radix {} string", radix));
+        }
+        if radix > BACKEND_MAX_RADIX {
+            let res = parse_digits(digits, radix);
+            return Ok(if negative { res.neg() } else { res });
+        }
+        let mut v = Vec::with_capacity(digits.len() + 1);
+        v.extend(digits.bytes().map(|c| c.to_ascii_lowercase()));
+        v.push(0);
+        let mut backend = MaybeUninit::uninit();
+        if unsafe { cry_mpi_init_str(backend.as_mut_ptr(), radix, v.as_ptr() as *const i8) } != 0 {
+            return Err(format!("Invalid radix {} string", radix));
+        }
+        let res = Mpi {
+            backend: unsafe { backend.assume_init() },
+        };
+        Ok(if negative { res.neg() } else { res })
+    }
+
+    /// String representation in the given radix, with a leading `-` for
+    /// negative numbers.
+    ///
+    /// Supported radixes are in the range `[2, 36]`, digits above 9 are
+    /// lowercase letters.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = self.abs_str_radix(radix);
+        if self.is_negative() {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+
+    // Lowercase representation of the absolute value.
+    fn abs_str_radix(&self, radix: u32) -> String {
+        assert!((2..=36).contains(&radix), "Unsupported radix {}", radix);
+        if self.is_zero() {
+            return "0".into();
+        }
+        let abs = self.abs();
+        if radix > BACKEND_MAX_RADIX {
+            return format_digits(&abs, radix);
+        }
+        // Radix 2 is the worst case, plus the string terminator.
+        let mut bytes: Vec<u8> = vec![0; abs.bits_count() + 2];
+        checked!(cry_mpi_store_str(
+            &abs.backend,
+            radix,
+            bytes.as_mut_ptr() as *mut i8
+        ));
+        let len = bytes.iter().position(|&c| c == 0).unwrap_or(bytes.len());
+        bytes.truncate(len);
+        bytes.make_ascii_lowercase();
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+// Largest radix handled by the backend string conversions.
+const BACKEND_MAX_RADIX: u32 = 16;
+
+// Largest power of `radix` fitting a single digit, with its exponent.
+fn chunk_base(radix: u32) -> (u64, usize) {
+    let (mut base, mut len) = (radix as u64, 1);
+    while let Some(next) = base.checked_mul(radix as u64) {
+        base = next;
+        len += 1;
+    }
+    (base, len)
+}
+
+// Horner's rule on chunks of valid digits, each one a single digit product.
+fn parse_digits(digits: &str, radix: u32) -> Mpi {
+    let (_, len) = chunk_base(radix);
+    digits
+        .as_bytes()
+        .chunks(len)
+        .fold(Mpi::new(), |acc, chunk| {
+            let value = chunk.iter().fold(0u64, |v, &c| {
+                let d = (c as char).to_digit(radix).expect("Valid digit");
+                v * radix as u64 + d as u64
+            });
+            &(&acc * (radix as u64).pow(chunk.len() as u32)) + value
+        })
+}
+
+// Digits of a positive number, by repeated division by the chunk base.
+fn format_digits(abs: &Mpi, radix: u32) -> String {
+    let (base, len) = chunk_base(radix);
+    let base = Mpi::from(base);
+    let mut chunks = Vec::new();
+    let mut x = abs.clone();
+    while !x.is_zero() {
+        let (q, r) = x.div_rem(&base);
+        chunks.push(u64::try_from(&r).expect("Single digit"));
+        x = q;
+    }
+    let mut digits = Vec::with_capacity(chunks.len() * len);
+    for mut chunk in chunks {
+        for _ in 0..len {
+            let d = (chunk % radix as u64) as u32;
+            digits.push(char::from_digit(d, radix).expect("Valid digit"));
+            chunk /= radix as u64;
+        }
+    }
+    while digits.last() == Some(&'0') {
+        digits.pop();
+    }
+    digits.iter().rev().collect()
+}
+
+impl FromStr for Mpi {
+    type Err = String;
+
+    /// Parses a decimal number.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Mpi::from_str_radix(s, 10)
+    }
+}
+
+impl Display for Mpi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.is_negative(), "", &self.abs_str_radix(10))
+    }
+}
+
+impl LowerHex for Mpi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.is_negative(), "0x", &self.abs_str_radix(16))
+    }
+}
+
+impl UpperHex for Mpi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let digits = self.abs_str_radix(16).to_ascii_uppercase();
+        f.pad_integral(!self.is_negative(), "0x", &digits)
+    }
+}
+
+impl Binary for Mpi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.is_negative(), "0b", &self.abs_str_radix(2))
+    }
+}
+
+impl Octal for Mpi {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.is_negative(), "0o", &self.abs_str_radix(8))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A_HEX: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68e1f9";
+    const A_DEC: &str =
+        "105026669356957778596897143882206757213422783161796641844673243687103459025401";
+    const A_OCT: &str =
+        "16406277177646403573075042362556507122433527515365643137650374710320070176775432160771";
+    const A_B36: &str = "5sccxecnt4v4hjra6eyc235dd9glxhx86ehiqywlqowfralkop";
+
+    #[test]
+    fn display_is_decimal() {
+        let a = Mpi::from_hex(A_HEX).unwrap();
+
+        assert_eq!(a.to_string(), A_DEC);
+        assert_eq!(a.neg().to_string(), format!("-{}", A_DEC));
+        assert_eq!(Mpi::new().to_string(), "0");
+    }
+
+    #[test]
+    fn from_str() {
+        let a: Mpi = A_DEC.parse().unwrap();
+        let b: Mpi = format!("-{}", A_DEC).parse().unwrap();
+
+        assert_eq!(a, Mpi::from_hex(A_HEX).unwrap());
+        assert_eq!(b, a.neg());
+        assert!("".parse::<Mpi>().is_err());
+        assert!("-".parse::<Mpi>().is_err());
+        assert!("12a".parse::<Mpi>().is_err());
+    }
+
+    #[test]
+    fn str_radix_roundtrip() {
+        let a = Mpi::from_hex(A_HEX).unwrap();
+
+        for radix in 2..=36 {
+            for x in [a.clone(), a.neg(), Mpi::new()] {
+                let s = x.to_str_radix(radix);
+                assert_eq!(Mpi::from_str_radix(&s, radix).unwrap(), x);
+            }
+        }
+        assert_eq!(a.to_str_radix(8), A_OCT);
+        assert!(Mpi::from_str_radix("102", 2).is_err());
+        assert_eq!(a.neg().to_str_radix(36), format!("-{}", A_B36));
+        assert_eq!(Mpi::from_str_radix("ZZ", 36).unwrap(), Mpi::from(1295i64));
+        assert!(Mpi::from_str_radix("1", 37).is_err());
+        assert!(Mpi::from_str_radix("1", 1).is_err());
+    }
+
+    #[test]
+    fn radix_formatting() {
+        let a = Mpi::from(-42i64);
+
+        assert_eq!(format!("{:x}", a), "-2a");
+        assert_eq!(format!("{:#X}", a), "-0x2A");
+        assert_eq!(format!("{:#b}", a), "-0b101010");
+        assert_eq!(format!("{:o}", a), "-52");
+        assert_eq!(format!("{:#010x}", a.neg()), "0x0000002a");
+        assert_eq!(format!("{:>6}", a), "   -42");
+        assert_eq!(
+            format!("{:X}", Mpi::from_hex(A_HEX).unwrap()),
+            A_HEX.to_uppercase()
+        );
+    }
+}
@@ -0,0 +1,658 @@
+use core::{
+    cmp::Ordering,
+    mem::MaybeUninit,
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign},
+};
+use cry_sys::bindings::{
+    cry_mpi, cry_mpi_abs, cry_mpi_add, cry_mpi_clear, cry_mpi_cmp, cry_mpi_copy,
+    cry_mpi_count_bits, cry_mpi_digit, cry_mpi_div, cry_mpi_init, cry_mpi_init_int,
+    cry_mpi_load_bin, cry_mpi_mod_exp, cry_mpi_mul, cry_mpi_sub,
+};
+
+// Same layout as the backend type, so that FFI structures holding numbers
+// by value can be mirrored with `Mpi` fields.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct Mpi {
+    backend: cry_mpi,
+}
+
+// We're not supposed to recover from this type of errors here.
+macro_rules! checked {
+    ($op:expr) => {
+        if unsafe { $op } != 0 {
+            panic!("Out of memory error");
+        }
+    };
+}
+
+mod bits;
+mod fmt;
+mod modular;
+mod mul;
+mod ntheory;
+#[cfg(feature = "num")]
+mod num;
+mod ops;
+mod prime;
+mod rand;
+mod scratch;
+
+pub use modular::{Modulus, Residue};
+pub use mul::{MulAlgorithm, MulThresholds};
+pub use ntheory::crt;
+pub use scratch::Scratch;
+
+impl Mpi {
+    pub fn new() -> Self {
+        let mut backend = unsafe { MaybeUninit::uninit().assume_init() };
+        checked!(cry_mpi_init(&mut backend));
+        Mpi { backend }
+    }
+
+    pub fn from_hex(x: &str) -> Result<Self, String> {
+        Mpi::from_str_radix(x, 16).map_err(|_| "Invalid hex string".into())
+    }
+
+    fn from_int(val: i64) -> Self {
+        let mut backend = MaybeUninit::uninit();
+        checked!(cry_mpi_init_int(backend.as_mut_ptr(), val));
+        Mpi {
+            backend: unsafe { backend.assume_init() },
+        }
+    }
+
+    /// Non-negative integer from its big-endian representation.
+    pub fn from_bytes_be(bytes: &[u8]) -> Self {
+        let mut res = Mpi::new();
+        checked!(cry_mpi_load_bin(
+            &mut res.backend,
+            bytes.as_ptr() as *const _,
+            bytes.len() as u64
+        ));
+        res
+    }
+
+    /// Big-endian representation of the absolute value, without leading zeros.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let bytes: Vec<u8> = self
+            .digits()
+            .iter()
+            .rev()
+            .flat_map(|d| d.to_be_bytes())
+            .collect();
+        let skip = bytes.len() - self.bytes_count();
+        bytes[skip..].to_vec()
+    }
+
+    // Little-endian digits of the absolute value.
+    pub(crate) fn digits(&self) -> &[cry_mpi_digit] {
+        if self.backend.used == 0 || self.backend.data.is_null() {
+            return &[];
+        }
+        unsafe { core::slice::from_raw_parts(self.backend.data, self.backend.used as usize) }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits().iter().all(|&d| d == 0)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.backend.sign != 0 && !self.is_zero()
+    }
+
+    pub fn is_odd(&self) -> bool {
+        self.digits().first().is_some_and(|d| d & 1 == 1)
+    }
+
+    // Absolute value modulo a small number.
+    fn rem_u32(&self, m: u32) -> u32 {
+        let m = m as u128;
+        self.digits()
+            .iter()
+            .rev()
+            .fold(0, |r, &d| ((r << 64) | d as u128) % m) as u32
+    }
+
+    pub fn bits_count(&self) -> usize {
+        unsafe { cry_mpi_count_bits(&self.backend) as usize }
+    }
+
+    pub fn bytes_count(&self) -> usize {
+        (self.bits_count() + 7) / 8
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        let mut res = Mpi::new();
+        self.add_into(other, &mut res);
+        res
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        let mut res = Mpi::new();
+        self.sub_into(other, &mut res);
+        res
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        let mut res = Mpi::new();
+        self.mul_into(other, &mut res);
+        res
+    }
+
+    // Returns quotiend and reminder tuple.
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let mut q = Mpi::new();
+        let mut r = Mpi::new();
+        self.div_rem_into(other, &mut q, &mut r);
+        (q, r)
+    }
+
+    pub fn div(&self, other: &Self) -> Self {
+        let mut res = Mpi::new();
+        self.div_into(other, &mut res);
+        res
+    }
+
+    pub fn rem(&self, other: &Self) -> Self {
+        let mut res = Mpi::new();
+        self.rem_into(other, &mut res);
+        res
+    }
+
+    pub fn neg(&self) -> Self {
+        Self::sub(&Mpi::new(), self)
+    }
+
+    pub fn abs(&self) -> Self {
+        let mut res = Mpi::new();
+        checked!(cry_mpi_abs(&mut res.backend, &self.backend));
+        res
+    }
+
+    pub fn mod_exp(&self, exp: &Self, modulus: &Self) -> Self {
+        let mut res = Mpi::new();
+        self.mod_exp_into(exp, modulus, &mut res);
+        res
+    }
+
+    // The `*_into` variants store the result in `dst`, reusing its storage.
+
+    pub fn add_into(&self, other: &Self, dst: &mut Mpi) {
+        checked!(cry_mpi_add(&mut dst.backend, &self.backend, &other.backend));
+    }
+
+    pub fn sub_into(&self, other: &Self, dst: &mut Mpi) {
+        checked!(cry_mpi_sub(&mut dst.backend, &self.backend, &other.backend));
+    }
+
+    pub fn mul_into(&self, other: &Self, dst: &mut Mpi) {
+        checked!(cry_mpi_mul(&mut dst.backend, &self.backend, &other.backend));
+    }
+
+    pub fn div_rem_into(&self, other: &Self, quot: &mut Mpi, rem: &mut Mpi) {
+        if let Some((q, r)) = self.signed_div_rem(other) {
+            (*quot, *rem) = (q, r);
+            return;
+        }
+        checked!(cry_mpi_div(
+            &mut quot.backend,
+            &mut rem.backend,
+            &self.backend,
+            &other.backend
+        ));
+    }
+
+    pub fn div_into(&self, other: &Self, dst: &mut Mpi) {
+        if let Some((q, _)) = self.signed_div_rem(other) {
+            *dst = q;
+            return;
+        }
+        checked!(cry_mpi_div(
+            &mut dst.backend,
+            core::ptr::null_mut(),
+            &self.backend,
+            &other.backend
+        ));
+    }
+
+    pub fn rem_into(&self, other: &Self, dst: &mut Mpi) {
+        if let Some((_, r)) = self.signed_div_rem(other) {
+            *dst = r;
+            return;
+        }
+        checked!(cry_mpi_div(
+            core::ptr::null_mut(),
+            &mut dst.backend,
+            &self.backend,
+            &other.backend
+        ));
+    }
+
+    // Truncated division of negative operands, as for the primitive integers:
+    // the quotient is rounded toward zero and the remainder has the sign of
+    // the dividend. The backend only divides the magnitudes, so the result
+    // doesn't depend on its sign conventions. `None` if both operands are
+    // non-negative.
+    fn signed_div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if !self.is_negative() && !other.is_negative() {
+            return None;
+        }
+        let (mut q, mut r) = self.abs().div_rem(&other.abs());
+        if self.is_negative() != other.is_negative() {
+            q = q.neg();
+        }
+        if self.is_negative() {
+            r = r.neg();
+        }
+        Some((q, r))
+    }
+
+    pub fn mod_exp_into(&self, exp: &Self, modulus: &Self, dst: &mut Mpi) {
+        checked!(cry_mpi_mod_exp(
+            &mut dst.backend,
+            &self.backend,
+            &exp.backend,
+            &modulus.backend
+        ));
+    }
+
+    pub fn add_assign(&mut self, other: &Self) {
+        checked!(cry_mpi_add(
+            &mut self.backend,
+            &self.backend,
+            &other.backend
+        ));
+    }
+
+    pub fn sub_assign(&mut self, other: &Self) {
+        checked!(cry_mpi_sub(
+            &mut self.backend,
+            &self.backend,
+            &other.backend
+        ));
+    }
+
+    pub fn mul_assign(&mut self, other: &Self) {
+        checked!(cry_mpi_mul(
+            &mut self.backend,
+            &self.backend,
+            &other.backend
+        ));
+    }
+
+    pub fn div_assign(&mut self, other: &Self) {
+        if let Some((q, _)) = self.signed_div_rem(other) {
+            *self = q;
+            return;
+        }
+        checked!(cry_mpi_div(
+            &mut self.backend,
+            core::ptr::null_mut(),
+            &self.backend,
+            &other.backend
+        ));
+    }
+
+    pub fn rem_assign(&mut self, other: &Self) {
+        if let Some((_, r)) = self.signed_div_rem(other) {
+            *self = r;
+            return;
+        }
+        checked!(cry_mpi_div(
+            core::ptr::null_mut(),
+            &mut self.backend,
+            &self.backend,
+            &other.backend
+        ));
+    }
+}
+
+impl Drop for Mpi {
+    fn drop(&mut self) {
+        unsafe {
+            cry_mpi_clear(&mut self.backend);
+        }
+    }
+}
+
+impl Clone for Mpi {
+    fn clone(&self) -> Self {
+        let mut res = Mpi::new();
+        res.clone_from(self);
+        res
+    }
+
+    fn clone_from(&mut self, source: &Self) {
+        checked!(cry_mpi_copy(&mut self.backend, &source.backend));
+    }
+}
+
+impl From<i64> for Mpi {
+    fn from(val: i64) -> Self {
+        Mpi::from_int(val)
+    }
+}
+
+impl From<u64> for Mpi {
+    fn from(val: u64) -> Self {
+        match i64::try_from(val) {
+            Ok(val) => Mpi::from_int(val),
+            Err(_) => Mpi::from_bytes_be(&val.to_be_bytes()),
+        }
+    }
+}
+
+impl TryFrom<&Mpi> for i64 {
+    type Error = String;
+
+    fn try_from(value: &Mpi) -> Result<Self, Self::Error> {
+        if value.bits_count() > 64 {
+            return Err("Value out of range".into());
+        }
+        let abs = value.digits().first().copied().unwrap_or(0);
+        let res = if value.is_negative() {
+            0i64.checked_sub_unsigned(abs)
+        } else {
+            i64::try_from(abs).ok()
+        };
+        res.ok_or_else(|| "Value out of range".into())
+    }
+}
+
+impl TryFrom<&Mpi> for u64 {
+    type Error = String;
+
+    fn try_from(value: &Mpi) -> Result<Self, Self::Error> {
+        if value.is_negative() || value.bits_count() > 64 {
+            return Err("Value out of range".into());
+        }
+        Ok(value.digits().first().copied().unwrap_or(0))
+    }
+}
+
+impl Add for Mpi {
+    type Output = Mpi;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::add(&self, &rhs)
+    }
+}
+
+impl Sub for Mpi {
+    type Output = Mpi;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::sub(&self, &rhs)
+    }
+}
+
+impl Mul for Mpi {
+    type Output = Mpi;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::mul(&self, &rhs)
+    }
+}
+
+impl Div for Mpi {
+    type Output = Mpi;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Self::div(&self, &rhs)
+    }
+}
+
+impl Rem for Mpi {
+    type Output = Mpi;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        Self::rem(&self, &rhs)
+    }
+}
+
+impl Neg for Mpi {
+    type Output = Mpi;
+
+    fn neg(self) -> Self::Output {
+        Self::neg(&self)
+    }
+}
+
+impl AddAssign for Mpi {
+    fn add_assign(&mut self, rhs: Self) {
+        Self::add_assign(self, &rhs);
+    }
+}
+
+impl SubAssign for Mpi {
+    fn sub_assign(&mut self, rhs: Self) {
+        Self::sub_assign(self, &rhs);
+    }
+}
+
+impl MulAssign for Mpi {
+    fn mul_assign(&mut self, rhs: Self) {
+        Self::mul_assign(self, &rhs);
+    }
+}
+
+impl DivAssign for Mpi {
+    fn div_assign(&mut self, rhs: Self) {
+        Self::div_assign(self, &rhs);
+    }
+}
+
+impl RemAssign for Mpi {
+    fn rem_assign(&mut self, rhs: Self) {
+        Self::rem_assign(self, &rhs);
+    }
+}
+
+impl PartialEq for Mpi {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Mpi {}
+
+impl PartialOrd for Mpi {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Mpi {
+    fn cmp(&self, other: &Self) -> Ordering {
+        unsafe { cry_mpi_cmp(&self.backend, &other.backend) }.cmp(&0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A_HEX: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68e1f9";
+    const B_HEX: &str = "6ba218432ef91f921a3dfe1b19725e61274cf66f76b5297c6ccd95c0d3d20ac1";
+    const C_HEX: &str = "4ca6087f5d5f081fbe7a305def082458ae2c5020b2b6116253dc62a8beab09ac";
+
+    const ADD_RES: &str = "153d51542d57afe59bc6593d5a7c4ec3710fc5388f6062660a0d49db0c03aecba";
+    const SUB_RES: &str = "7c90e4bc7788bf3587e9979f74e02f74c26266aa089bd367c739722f1896d738";
+    const MUL_RES: &str = "61a05604ac0af481e2141fb88a9a3958d46bf7925dd4273091e10b1be8d4e6b41805b66838d5759f1cdb7289b546945da92482d639d088db1cdb2326ed2816b9";
+    const DIV_RES: &str = "2";
+    const REM_RES: &str = "10eecc79488f9fa36dab99845b6dd1139b15703a91e6a9eb5a6bdc6e44c4cc77";
+    const MOD_EXP_RES: &str = "3c544e8ea7a083b08e08975f28e2a4673d421c6956f93a1aa65a204e03eb87e1";
+
+    fn from_hex(s: &str) -> Mpi {
+        Mpi::from_hex(s).unwrap()
+    }
+
+    #[test]
+    fn add() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        let c = a + b;
+
+        assert_eq!(format!("{:x}", c), ADD_RES);
+    }
+
+    #[test]
+    fn sub() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        let c = a - b;
+
+        assert_eq!(format!("{:x}", c), SUB_RES);
+    }
+
+    #[test]
+    fn mul() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        let c = a * b;
+
+        assert_eq!(format!("{:x}", c), MUL_RES);
+    }
+
+    #[test]
+    fn div() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        let c = a / b;
+
+        assert_eq!(format!("{:x}", c), DIV_RES);
+    }
+
+    #[test]
+    fn rem() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        let c = a % b;
+
+        assert_eq!(format!("{:x}", c), REM_RES);
+    }
+
+    #[test]
+    fn signed_div_rem() {
+        let int = |x: i64| Mpi::from(x);
+        for (a, b) in [(7, 2), (-7, 2), (7, -2), (-7, -2), (-6, 3), (0, -5)] {
+            let (q, r) = int(a).div_rem(&int(b));
+            assert_eq!((q, r), (int(a / b), int(a % b)));
+            let mut x = int(a);
+            x /= int(b);
+            assert_eq!(x, int(a / b));
+            let mut x = int(a);
+            x %= int(b);
+            assert_eq!(x, int(a % b));
+        }
+    }
+
+    #[test]
+    fn add_assign() {
+        let a = from_hex(A_HEX);
+        let mut b = from_hex(B_HEX);
+
+        b += a;
+
+        assert_eq!(format!("{:x}", b), ADD_RES);
+    }
+
+    #[test]
+    fn sub_assign() {
+        let mut a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        a -= b;
+
+        assert_eq!(format!("{:x}", a), SUB_RES);
+    }
+
+    #[test]
+    fn mul_assign() {
+        let a = from_hex(A_HEX);
+        let mut b = from_hex(B_HEX);
+
+        b *= a;
+
+        assert_eq!(format!("{:x}", b), MUL_RES);
+    }
+
+    #[test]
+    fn div_assign() {
+        let mut a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        a /= b;
+
+        assert_eq!(format!("{:x}", a), DIV_RES);
+    }
+
+    #[test]
+    fn rem_assign() {
+        let mut a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        a %= b;
+
+        assert_eq!(format!("{:x}", a), REM_RES);
+    }
+
+    #[test]
+    fn neg() {
+        let a = from_hex(A_HEX);
+
+        let c = -a.clone();
+
+        assert!(c.is_negative());
+        assert!((c.clone() + a.clone()).is_zero());
+        assert_eq!(c.abs(), a);
+        assert_eq!(-c, a);
+    }
+
+    #[test]
+    fn int_conversions() {
+        for val in [0, 1, -1, i64::MAX, i64::MIN] {
+            assert_eq!(i64::try_from(&Mpi::from(val)), Ok(val));
+        }
+        for val in [0, 1, i64::MAX as u64 + 1, u64::MAX] {
+            assert_eq!(u64::try_from(&Mpi::from(val)), Ok(val));
+        }
+        assert_eq!(format!("{:x}", Mpi::from(u64::MAX)), "ffffffffffffffff");
+        assert_eq!(Mpi::from(-1i64) + Mpi::from(1u64), Mpi::new());
+
+        assert!(i64::try_from(&Mpi::from(u64::MAX)).is_err());
+        assert!(i64::try_from(&Mpi::from(i64::MAX as u64 + 1)).is_err());
+        assert!(i64::try_from(&(Mpi::from(i64::MIN) - 1i64)).is_err());
+        assert!(u64::try_from(&Mpi::from(-1i64)).is_err());
+        assert!(u64::try_from(&from_hex(A_HEX)).is_err());
+    }
+
+    #[test]
+    fn bytes_conversions() {
+        let a = from_hex(A_HEX);
+
+        let bytes = a.to_bytes_be();
+
+        assert_eq!(hex::encode(&bytes), A_HEX);
+        assert_eq!(Mpi::from_bytes_be(&bytes), a);
+        assert_eq!(Mpi::from_bytes_be(&[0, 0, 1, 2]).to_bytes_be(), [1, 2]);
+        assert!(Mpi::new().to_bytes_be().is_empty());
+    }
+
+    #[test]
+    fn mod_exp() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+        let c = from_hex(C_HEX);
+
+        let c = a.mod_exp(&b, &c);
+
+        assert_eq!(format!("{:x}", c), MOD_EXP_RES);
+    }
+}
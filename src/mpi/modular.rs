@@ -0,0 +1,319 @@
+use super::Mpi;
+use core::ops::{Add, Mul, Neg, Sub};
+use cry_sys::bindings::cry_mpi_inv;
+
+impl Mpi {
+    /// Modular multiplicative inverse, if it exists.
+    pub fn mod_inv(&self, modulus: &Self) -> Option<Self> {
+        let mut res = Mpi::new();
+        let ret = unsafe { cry_mpi_inv(&mut res.backend, &self.backend, &modulus.backend) };
+        let one = Mpi::from_int(1);
+        if ret != 0 || Mpi::rem(&Mpi::mul(self, &res), modulus) != one {
+            return None;
+        }
+        if res.is_negative() {
+            res.add_assign(modulus);
+        }
+        Some(res)
+    }
+}
+
+/// Modulus with precomputed Barrett reduction constants.
+///
+/// Reducing a product of two residues costs two multiplications and two shifts
+/// instead of a full division.
+#[derive(Debug, Clone)]
+pub struct Modulus {
+    n: Mpi,
+    // floor(2^(2k) / n)
+    mu: Mpi,
+    // Bit length of `n`
+    k: usize,
+}
+
+impl Modulus {
+    pub fn new(n: Mpi) -> Result<Self, String> {
+        if n <= Mpi::from_int(1) {
+            return Err("Modulus must be greater than one".into());
+        }
+        let k = n.bits_count();
+        let mu = Mpi::from_int(1).shl(2 * k).div(&n);
+        Ok(Modulus { n, mu, k })
+    }
+
+    pub fn value(&self) -> &Mpi {
+        &self.n
+    }
+
+    /// Reduces any integer in the range `[0, n)`.
+    pub fn reduce(&self, x: &Mpi) -> Mpi {
+        if x.is_negative() || x.bits_count() > 2 * self.k {
+            let mut r = x.rem(&self.n);
+            if r.is_negative() {
+                r.add_assign(&self.n);
+            }
+            return r;
+        }
+        self.barrett(x)
+    }
+
+    // Requires `0 <= x < 2^(2k)`.
+    fn barrett(&self, x: &Mpi) -> Mpi {
+        let q = x.shr(self.k - 1).mul(&self.mu).shr(self.k + 1);
+        let mut r = x.sub(&q.mul(&self.n));
+        while r >= self.n {
+            r.sub_assign(&self.n);
+        }
+        r
+    }
+
+    pub fn residue(&self, x: &Mpi) -> Residue<'_> {
+        Residue {
+            value: self.reduce(x),
+            modulus: self,
+        }
+    }
+
+    pub fn zero(&self) -> Residue<'_> {
+        Residue {
+            value: Mpi::new(),
+            modulus: self,
+        }
+    }
+
+    pub fn one(&self) -> Residue<'_> {
+        self.residue(&Mpi::from_int(1))
+    }
+}
+
+/// Integer modulo a `Modulus`, always kept in the range `[0, n)`.
+///
+/// Operations between residues of different moduli panic.
+#[derive(Debug, Clone)]
+pub struct Residue<'m> {
+    value: Mpi,
+    modulus: &'m Modulus,
+}
+
+impl<'m> Residue<'m> {
+    pub fn value(&self) -> &Mpi {
+        &self.value
+    }
+
+    pub fn into_value(self) -> Mpi {
+        self.value
+    }
+
+    pub fn modulus(&self) -> &'m Modulus {
+        self.modulus
+    }
+
+    fn with_value(&self, value: Mpi) -> Self {
+        Residue {
+            value,
+            modulus: self.modulus,
+        }
+    }
+
+    fn check_modulus(&self, other: &Self) {
+        assert!(
+            core::ptr::eq(self.modulus, other.modulus) || self.modulus.n == other.modulus.n,
+            "Residues with different moduli"
+        );
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        self.check_modulus(other);
+        let mut r = &self.value + &other.value;
+        if r >= self.modulus.n {
+            r.sub_assign(&self.modulus.n);
+        }
+        self.with_value(r)
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.check_modulus(other);
+        let mut r = &self.value - &other.value;
+        if r.is_negative() {
+            r.add_assign(&self.modulus.n);
+        }
+        self.with_value(r)
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        self.check_modulus(other);
+        self.with_value(self.modulus.barrett(&(&self.value * &other.value)))
+    }
+
+    pub fn square(&self) -> Self {
+        self.with_value(self.modulus.barrett(&self.value.square()))
+    }
+
+    pub fn neg(&self) -> Self {
+        if self.value.is_zero() {
+            return self.clone();
+        }
+        self.with_value(&self.modulus.n - &self.value)
+    }
+
+    pub fn inv(&self) -> Option<Self> {
+        self.value
+            .mod_inv(&self.modulus.n)
+            .map(|v| self.with_value(v))
+    }
+
+    /// Exponentiation, negative exponents are allowed for invertible values.
+    ///
+    /// Left-to-right square and multiply, each step with a Barrett reduction.
+    pub fn pow(&self, exp: &Mpi) -> Option<Self> {
+        let base = if exp.is_negative() {
+            self.inv()?
+        } else {
+            self.clone()
+        };
+        let exp = exp.abs();
+        let mut acc = self.modulus.one();
+        for i in (0..exp.bits_count()).rev() {
+            acc = acc.square();
+            if exp.bit(i) {
+                acc = acc.mul(&base);
+            }
+        }
+        Some(acc)
+    }
+}
+
+impl PartialEq for Residue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.modulus.n == other.modulus.n && self.value == other.value
+    }
+}
+
+impl Eq for Residue<'_> {}
+
+macro_rules! impl_residue_op {
+    ($op:ident, $fn:ident) => {
+        impl<'m> $op for Residue<'m> {
+            type Output = Residue<'m>;
+
+            fn $fn(self, rhs: Self) -> Self::Output {
+                Residue::$fn(&self, &rhs)
+            }
+        }
+
+        impl<'m> $op<&Residue<'m>> for Residue<'m> {
+            type Output = Residue<'m>;
+
+            fn $fn(self, rhs: &Residue<'m>) -> Self::Output {
+                Residue::$fn(&self, rhs)
+            }
+        }
+
+        impl<'m> $op for &Residue<'m> {
+            type Output = Residue<'m>;
+
+            fn $fn(self, rhs: Self) -> Self::Output {
+                Residue::$fn(self, rhs)
+            }
+        }
+    };
+}
+
+impl_residue_op!(Add, add);
+impl_residue_op!(Sub, sub);
+impl_residue_op!(Mul, mul);
+
+impl<'m> Neg for Residue<'m> {
+    type Output = Residue<'m>;
+
+    fn neg(self) -> Self::Output {
+        Residue::neg(&self)
+    }
+}
+
+impl<'m> Neg for &Residue<'m> {
+    type Output = Residue<'m>;
+
+    fn neg(self) -> Self::Output {
+        Residue::neg(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // P-256 field prime and an even modulus
+    const P_HEX: &str = "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff";
+    const EVEN_HEX: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68e1f8";
+
+    #[test]
+    fn arithmetic_matches_mpi() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+
+        for n_hex in [P_HEX, EVEN_HEX, "3", "10001"] {
+            let n = Mpi::from_hex(n_hex).unwrap();
+            let modulus = Modulus::new(n.clone()).unwrap();
+
+            for _ in 0..20 {
+                let a = Mpi::random_below(&n, &mut rng);
+                let b = Mpi::random_below(&n, &mut rng);
+                let ra = modulus.residue(&a);
+                let rb = modulus.residue(&b);
+
+                let expected = |x: Mpi| modulus.reduce(&x);
+                assert_eq!(*(&ra + &rb).value(), expected(&a + &b));
+                assert_eq!(*(&ra - &rb).value(), expected(&a - &b));
+                assert_eq!(*(&ra * &rb).value(), &(&a * &b) % &n);
+                assert_eq!(*ra.square().value(), &(&a * &a) % &n);
+                assert_eq!((-&ra + ra.clone()), modulus.zero());
+            }
+        }
+    }
+
+    #[test]
+    fn pow_matches_mod_exp() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let n = Mpi::from_hex(P_HEX).unwrap();
+        let modulus = Modulus::new(n.clone()).unwrap();
+
+        for _ in 0..10 {
+            let a = Mpi::random_below(&n, &mut rng);
+            let e = Mpi::random_bits(256, &mut rng);
+            let ra = modulus.residue(&a);
+
+            assert_eq!(*ra.pow(&e).unwrap().value(), a.mod_exp(&e, &n));
+        }
+        let a = modulus.residue(&Mpi::from(7i64));
+        assert_eq!(a.pow(&Mpi::new()).unwrap(), modulus.one());
+        assert_eq!(a.pow(&Mpi::from(1i64)).unwrap(), a);
+    }
+
+    #[test]
+    fn inverse() {
+        let n = Mpi::from_hex(P_HEX).unwrap();
+        let modulus = Modulus::new(n.clone()).unwrap();
+        let a = modulus.residue(&Mpi::from_hex(EVEN_HEX).unwrap());
+
+        let inv = a.inv().unwrap();
+
+        assert_eq!(&inv * &a, modulus.one());
+        assert_eq!(a.pow(&Mpi::from(-1i64)).unwrap(), inv);
+
+        let even = Modulus::new(Mpi::from_hex(EVEN_HEX).unwrap()).unwrap();
+        assert!(even.residue(&Mpi::from(6u64)).inv().is_none());
+        assert!(even.zero().inv().is_none());
+    }
+
+    #[test]
+    fn reduce_out_of_range() {
+        let n = Mpi::from_hex(P_HEX).unwrap();
+        let modulus = Modulus::new(n.clone()).unwrap();
+        let x = Mpi::from_hex(&"f1".repeat(100)).unwrap();
+
+        assert_eq!(modulus.reduce(&x), &x % &n);
+        assert_eq!(modulus.reduce(&-x.clone()), &n - &(&x % &n));
+        assert!(Modulus::new(Mpi::from(1u64)).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use super::Mpi;
+use cry_sys::bindings::{
+    cry_mpi, cry_mpi_mul_baseline, cry_mpi_mul_comba, cry_mpi_mul_karatsuba, cry_mpi_mul_toom3,
+    cry_mpi_sqr, cry_mpi_sqr_baseline, cry_mpi_sqr_comba, cry_mpi_sqr_karatsuba,
+};
+use std::os::raw::c_int;
+
+type MulFn = unsafe extern "C" fn(*mut cry_mpi, *const cry_mpi, *const cry_mpi) -> c_int;
+type SqrFn = unsafe extern "C" fn(*mut cry_mpi, *const cry_mpi) -> c_int;
+
+/// Multiplication algorithms provided by the backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MulAlgorithm {
+    /// Schoolbook multiplication.
+    Baseline,
+    /// Schoolbook multiplication with column-wise accumulation.
+    Comba,
+    /// Karatsuba, `O(n^1.58)`.
+    Karatsuba,
+    /// Toom-Cook 3-way, `O(n^1.46)`.
+    Toom3,
+}
+
+impl MulAlgorithm {
+    fn mul_fn(self) -> MulFn {
+        match self {
+            MulAlgorithm::Baseline => cry_mpi_mul_baseline,
+            MulAlgorithm::Comba => cry_mpi_mul_comba,
+            MulAlgorithm::Karatsuba => cry_mpi_mul_karatsuba,
+            MulAlgorithm::Toom3 => cry_mpi_mul_toom3,
+        }
+    }
+
+    // The backend doesn't provide a Toom-3 specialized squaring.
+    fn sqr_fn(self) -> Option<SqrFn> {
+        match self {
+            MulAlgorithm::Baseline => Some(cry_mpi_sqr_baseline),
+            MulAlgorithm::Comba => Some(cry_mpi_sqr_comba),
+            MulAlgorithm::Karatsuba => Some(cry_mpi_sqr_karatsuba),
+            MulAlgorithm::Toom3 => None,
+        }
+    }
+}
+
+/// Automatic multiplication algorithm selection.
+///
+/// Each threshold is the operands size, in digits, from which the
+/// corresponding algorithm is used. For unbalanced operands the size of the
+/// smaller one is considered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MulThresholds {
+    pub comba: usize,
+    pub karatsuba: usize,
+    pub toom3: usize,
+}
+
+impl MulThresholds {
+    pub fn select(&self, digits: usize) -> MulAlgorithm {
+        if digits >= self.toom3 {
+            MulAlgorithm::Toom3
+        } else if digits >= self.karatsuba {
+            MulAlgorithm::Karatsuba
+        } else if digits >= self.comba {
+            MulAlgorithm::Comba
+        } else {
+            MulAlgorithm::Baseline
+        }
+    }
+}
+
+impl Default for MulThresholds {
+    fn default() -> Self {
+        MulThresholds {
+            comba: 0,
+            karatsuba: 48,
+            toom3: 160,
+        }
+    }
+}
+
+impl Mpi {
+    pub fn mul_with(&self, other: &Self, algo: MulAlgorithm) -> Self {
+        let mut res = Mpi::new();
+        checked!((algo.mul_fn())(
+            &mut res.backend,
+            &self.backend,
+            &other.backend
+        ));
+        // Sign is explicitly set as some algorithms work on absolute values.
+        res.backend.sign = (self.is_negative() != other.is_negative() && !res.is_zero()) as c_int;
+        res
+    }
+
+    pub fn square(&self) -> Self {
+        let mut res = Mpi::new();
+        self.square_into(&mut res);
+        res
+    }
+
+    /// Raises to the power `exp` by repeated squaring.
+    pub fn pow(&self, exp: u64) -> Self {
+        let mut res = Mpi::from_int(1);
+        for i in (0..u64::BITS - exp.leading_zeros()).rev() {
+            res = res.square();
+            if (exp >> i) & 1 == 1 {
+                res = Mpi::mul(&res, self);
+            }
+        }
+        res
+    }
+
+    pub fn square_into(&self, dst: &mut Mpi) {
+        checked!(cry_mpi_sqr(&mut dst.backend, &self.backend));
+    }
+
+    pub fn square_with(&self, algo: MulAlgorithm) -> Self {
+        let Some(sqr) = algo.sqr_fn() else {
+            return self.mul_with(self, algo);
+        };
+        let mut res = Mpi::new();
+        checked!(sqr(&mut res.backend, &self.backend));
+        res.backend.sign = 0;
+        res
+    }
+
+    /// Multiplication with the algorithm selected by `thresholds`.
+    pub fn mul_tuned(&self, other: &Self, thresholds: &MulThresholds) -> Self {
+        let digits = self.digits().len().min(other.digits().len());
+        self.mul_with(other, thresholds.select(digits))
+    }
+
+    /// Squaring with the algorithm selected by `thresholds`.
+    pub fn square_tuned(&self, thresholds: &MulThresholds) -> Self {
+        self.square_with(thresholds.select(self.digits().len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    const ALGORITHMS: [MulAlgorithm; 4] = [
+        MulAlgorithm::Baseline,
+        MulAlgorithm::Comba,
+        MulAlgorithm::Karatsuba,
+        MulAlgorithm::Toom3,
+    ];
+
+    #[test]
+    fn algorithms_agree() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let sizes = [
+            (1, 1),
+            (3, 3),
+            (17, 5),
+            (40, 40),
+            (100, 97),
+            (300, 300),
+            (400, 30),
+        ];
+
+        for (a_digits, b_digits) in sizes {
+            let a = Mpi::random_bits(64 * a_digits, &mut rng);
+            let b = Mpi::random_bits(64 * b_digits, &mut rng).neg();
+            let expected = a.mul(&b);
+
+            for algo in ALGORITHMS {
+                assert_eq!(a.mul_with(&b, algo), expected, "{:?}", algo);
+                assert_eq!(b.mul_with(&a, algo), expected, "{:?}", algo);
+                assert_eq!(b.square_with(algo), b.mul(&b), "{:?}", algo);
+            }
+        }
+    }
+
+    #[test]
+    fn zero_operand() {
+        let a = Mpi::from(-12345i64);
+
+        for algo in ALGORITHMS {
+            let c = a.mul_with(&Mpi::new(), algo);
+            assert!(c.is_zero());
+            assert!(!c.is_negative());
+        }
+    }
+
+    #[test]
+    fn pow() {
+        let a = Mpi::from(-3i64);
+
+        assert_eq!(a.pow(0), Mpi::from(1i64));
+        assert_eq!(a.pow(5), Mpi::from(-243i64));
+        assert_eq!(a.pow(40), Mpi::from(12157665459056928801u64));
+        assert_eq!(Mpi::from(2i64).pow(200), Mpi::from(1i64).shl(200));
+    }
+
+    #[test]
+    fn tuned_selection() {
+        let thresholds = MulThresholds {
+            comba: 2,
+            karatsuba: 8,
+            toom3: 32,
+        };
+
+        assert_eq!(thresholds.select(1), MulAlgorithm::Baseline);
+        assert_eq!(thresholds.select(2), MulAlgorithm::Comba);
+        assert_eq!(thresholds.select(31), MulAlgorithm::Karatsuba);
+        assert_eq!(thresholds.select(32), MulAlgorithm::Toom3);
+
+        let a = Mpi::from_hex(&"f1".repeat(1000)).unwrap();
+        assert_eq!(a.mul_tuned(&a, &thresholds), a.square());
+        assert_eq!(a.square_tuned(&MulThresholds::default()), a.square());
+    }
+}
@@ -0,0 +1,296 @@
+use super::Mpi;
+use cry_sys::bindings::{cry_mpi_gcd, cry_mpi_lcm};
+
+/// Solves the system `x = r_i mod m_i` using the Chinese Remainder Theorem.
+///
+/// Moduli must be positive and pairwise coprime. The result is the unique
+/// solution in `[0, m_1 * ... * m_k)`.
+pub fn crt(congruences: &[(Mpi, Mpi)]) -> Result<Mpi, String> {
+    let mut x = Mpi::new();
+    let mut m = Mpi::from_int(1);
+    for (r, mi) in congruences {
+        if mi.is_negative() || mi.is_zero() {
+            return Err("Moduli must be positive".into());
+        }
+        // Any x satisfies a congruence modulo 1
+        if mi == &Mpi::from_int(1) {
+            continue;
+        }
+        // x' = x + m * ((r - x) * m^-1 mod mi)
+        let inv = m
+            .mod_inv(mi)
+            .ok_or_else(|| String::from("Moduli are not pairwise coprime"))?;
+        let t = Mpi::mul(&(r - &x), &inv).rem_euclid(mi);
+        x.add_assign(&Mpi::mul(&m, &t));
+        m.mul_assign(mi);
+    }
+    Ok(x)
+}
+
+impl Mpi {
+    /// Greatest common divisor, always non-negative.
+    pub fn gcd(&self, other: &Self) -> Self {
+        let mut res = Mpi::new();
+        checked!(cry_mpi_gcd(&mut res.backend, &self.backend, &other.backend));
+        res.abs()
+    }
+
+    /// Least common multiple, always non-negative.
+    pub fn lcm(&self, other: &Self) -> Self {
+        let mut res = Mpi::new();
+        checked!(cry_mpi_lcm(&mut res.backend, &self.backend, &other.backend));
+        res.abs()
+    }
+
+    /// Remainder in the range `[0, |m|)`.
+    pub fn rem_euclid(&self, m: &Self) -> Self {
+        let mut r = self.rem(m);
+        if r.is_negative() {
+            r = Mpi::add(&r, &m.abs());
+        }
+        r
+    }
+
+    /// Jacobi symbol `(self / n)`, `n` must be odd and positive.
+    pub fn jacobi(&self, n: &Self) -> i32 {
+        assert!(
+            n.is_odd() && !n.is_negative(),
+            "Jacobi symbol of an invalid n"
+        );
+        let mut a = self.rem_euclid(n);
+        let mut n = n.clone();
+        let mut res = 1;
+        while !a.is_zero() {
+            let s = a.trailing_zeros();
+            a.shr_assign(s);
+            // (2 / n) = -1 iff n = 3, 5 mod 8
+            if s % 2 == 1 && matches!(n.rem_u32(8), 3 | 5) {
+                res = -res;
+            }
+            // Quadratic reciprocity
+            if a.rem_u32(4) == 3 && n.rem_u32(4) == 3 {
+                res = -res;
+            }
+            core::mem::swap(&mut a, &mut n);
+            a = a.rem(&n);
+        }
+        if n == Mpi::from_int(1) {
+            res
+        } else {
+            0
+        }
+    }
+
+    /// Legendre symbol `(self / p)`, `p` must be an odd prime.
+    ///
+    /// Returns `1` for quadratic residues, `-1` for non residues and `0` when
+    /// `p` divides `self`.
+    pub fn legendre(&self, p: &Self) -> i32 {
+        self.jacobi(p)
+    }
+
+    /// Square root modulo an odd prime `p`, if `self` is a quadratic residue.
+    ///
+    /// Uses a single exponentiation when `p = 3 mod 4` and Tonelli-Shanks
+    /// otherwise. The other root is `p` minus the returned one.
+    pub fn mod_sqrt(&self, p: &Self) -> Option<Self> {
+        let a = self.rem_euclid(p);
+        if a.is_zero() {
+            return Some(a);
+        }
+        if a.legendre(p) != 1 {
+            return None;
+        }
+        let one = Mpi::from_int(1);
+        let root = if p.rem_u32(4) == 3 {
+            a.mod_exp(&(p + &one).shr(2), p)
+        } else {
+            tonelli_shanks(&a, p)?
+        };
+        // Guards against a composite `p`.
+        (Mpi::mul(&root, &root).rem(p) == a).then_some(root)
+    }
+}
+
+// Requires `a` to be a non-zero quadratic residue modulo the odd prime `p`.
+// Returns `None` if the iteration doesn't converge, i.e. `p` is not prime.
+fn tonelli_shanks(a: &Mpi, p: &Mpi) -> Option<Mpi> {
+    let one = Mpi::from_int(1);
+    let p_minus_one = p - &one;
+    // p - 1 = q * 2^s, q odd
+    let s = p_minus_one.trailing_zeros();
+    let q = p_minus_one.shr(s);
+
+    // Any quadratic non residue; half of the candidates are.
+    let mut z = Mpi::from_int(2);
+    while z.legendre(p) != -1 {
+        z.add_assign(&one);
+        if &z == p {
+            return None;
+        }
+    }
+
+    let mut m = s;
+    let mut c = z.mod_exp(&q, p);
+    let mut t = a.mod_exp(&q, p);
+    let mut r = a.mod_exp(&(&q + &one).shr(1), p);
+    while t != one {
+        // Least i such that t^(2^i) = 1, for a prime p is less than m.
+        let mut i = 0;
+        let mut t2 = t.clone();
+        while t2 != one {
+            t2 = Mpi::mul(&t2, &t2).rem(p);
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+        let mut b = c;
+        for _ in 0..m - i - 1 {
+            b = Mpi::mul(&b, &b).rem(p);
+        }
+        m = i;
+        c = Mpi::mul(&b, &b).rem(p);
+        t = Mpi::mul(&t, &c).rem(p);
+        r = Mpi::mul(&r, &b).rem(p);
+    }
+    Some(r)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NIST P-256 field prime, p = 3 mod 4
+    const P256: &str = "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff";
+    // NIST P-224 field prime, p - 1 = q * 2^96
+    const P224: &str = "ffffffffffffffffffffffffffffffff000000000000000000000001";
+
+    fn from_hex(s: &str) -> Mpi {
+        Mpi::from_hex(s).unwrap()
+    }
+
+    fn pairs(values: &[(i64, i64)]) -> Vec<(Mpi, Mpi)> {
+        values
+            .iter()
+            .map(|&(r, m)| (Mpi::from_int(r), Mpi::from_int(m)))
+            .collect()
+    }
+
+    #[test]
+    fn crt_small() {
+        let x = crt(&pairs(&[(2, 3), (3, 5), (2, 7)])).unwrap();
+        assert_eq!(x, Mpi::from_int(23));
+
+        let x = crt(&pairs(&[(-1, 4), (10, 9)])).unwrap();
+        assert_eq!(x, Mpi::from_int(19));
+
+        assert_eq!(crt(&[]).unwrap(), Mpi::new());
+        let x = crt(&pairs(&[(5, 1), (2, 3), (4, 1)])).unwrap();
+        assert_eq!(x, Mpi::from_int(2));
+        assert_eq!(crt(&pairs(&[(7, 1)])).unwrap(), Mpi::new());
+    }
+
+    #[test]
+    fn crt_errors() {
+        assert!(crt(&pairs(&[(1, 6), (2, 9)])).is_err());
+        assert!(crt(&pairs(&[(1, 0)])).is_err());
+        assert!(crt(&pairs(&[(1, -5)])).is_err());
+    }
+
+    #[test]
+    fn crt_large() {
+        let p = from_hex(P256);
+        let q = from_hex(P224);
+        let x = from_hex("123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef0");
+
+        let res = crt(&[(x.rem(&p), p), (x.rem(&q), q)]).unwrap();
+
+        assert_eq!(res, x);
+    }
+
+    #[test]
+    fn gcd_lcm() {
+        let a = Mpi::from_int(84);
+        let b = Mpi::from_int(-90);
+
+        assert_eq!(a.gcd(&b), Mpi::from_int(6));
+        assert_eq!(a.lcm(&b), Mpi::from_int(1260));
+        assert_eq!(a.gcd(&Mpi::new()), a);
+        assert_eq!(from_hex(P256).gcd(&from_hex(P224)), Mpi::from_int(1));
+    }
+
+    #[test]
+    fn rem_euclid() {
+        let m = Mpi::from_int(7);
+
+        assert_eq!(Mpi::from_int(-3).rem_euclid(&m), Mpi::from_int(4));
+        assert_eq!(Mpi::from_int(-3).rem_euclid(&m.neg()), Mpi::from_int(4));
+        assert_eq!(Mpi::from_int(10).rem_euclid(&m), Mpi::from_int(3));
+        assert!(Mpi::from_int(-14).rem_euclid(&m).is_zero());
+    }
+
+    #[test]
+    fn jacobi() {
+        assert_eq!(Mpi::from_int(1001).jacobi(&Mpi::from_int(9907)), -1);
+        assert_eq!(Mpi::from_int(19).jacobi(&Mpi::from_int(45)), 1);
+        assert_eq!(Mpi::from_int(8).jacobi(&Mpi::from_int(21)), -1);
+        assert_eq!(Mpi::from_int(5).jacobi(&Mpi::from_int(21)), 1);
+        assert_eq!(Mpi::from_int(6).jacobi(&Mpi::from_int(15)), 0);
+        assert_eq!(Mpi::from_int(-1).jacobi(&Mpi::from_int(7)), -1);
+        assert_eq!(Mpi::from_int(5).jacobi(&Mpi::from_int(1)), 1);
+    }
+
+    #[test]
+    fn legendre_matches_euler_criterion() {
+        let p = Mpi::from_int(103);
+        let e = Mpi::from_int(51);
+
+        for a in 0..103 {
+            let a = Mpi::from_int(a);
+            let expected = match a.mod_exp(&e, &p) {
+                x if x.is_zero() => 0,
+                x if x == Mpi::from_int(1) => 1,
+                _ => -1,
+            };
+            assert_eq!(a.legendre(&p), expected);
+        }
+    }
+
+    #[test]
+    fn mod_sqrt_small() {
+        // 17 - 1 = 2^4, the worst case for Tonelli-Shanks
+        for p in [3, 5, 7, 13, 17, 41] {
+            let p = Mpi::from_int(p);
+            for a in 0..17 {
+                let a = Mpi::from_int(a);
+                match a.mod_sqrt(&p) {
+                    Some(r) => assert_eq!(Mpi::mul(&r, &r).rem(&p), a.rem(&p)),
+                    None => assert_eq!(a.legendre(&p), -1),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn mod_sqrt_large() {
+        for p in [from_hex(P256), from_hex(P224)] {
+            let x = from_hex("123456789abcdef0123456789abcdef0123456789abcdef");
+            let a = Mpi::mul(&x, &x).rem(&p);
+
+            let r = a.mod_sqrt(&p).unwrap();
+
+            assert!(r == x || r == &p - &x);
+            assert!((&p - &Mpi::from_int(1)).mod_sqrt(&p).is_some() == (p.rem_u32(4) == 1));
+        }
+    }
+
+    #[test]
+    fn mod_sqrt_composite() {
+        // Non residues modulo both factors, yet the Jacobi symbol is 1
+        assert_eq!(Mpi::from_int(2).jacobi(&Mpi::from_int(15)), 1);
+        assert!(Mpi::from_int(2).mod_sqrt(&Mpi::from_int(15)).is_none());
+        assert_eq!(Mpi::from_int(5).jacobi(&Mpi::from_int(21)), 1);
+        assert!(Mpi::from_int(5).mod_sqrt(&Mpi::from_int(21)).is_none());
+    }
+}
@@ -0,0 +1,315 @@
+use super::Mpi;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{FromPrimitive, Num, One, Pow, Signed, ToPrimitive, Zero};
+
+impl Zero for Mpi {
+    fn zero() -> Self {
+        Mpi::new()
+    }
+
+    fn is_zero(&self) -> bool {
+        Mpi::is_zero(self)
+    }
+}
+
+impl One for Mpi {
+    fn one() -> Self {
+        Mpi::from_int(1)
+    }
+}
+
+impl Num for Mpi {
+    type FromStrRadixErr = String;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        Mpi::from_str_radix(s, radix)
+    }
+}
+
+impl Signed for Mpi {
+    fn abs(&self) -> Self {
+        Mpi::abs(self)
+    }
+
+    fn abs_sub(&self, other: &Self) -> Self {
+        if self <= other {
+            Mpi::new()
+        } else {
+            Mpi::sub(self, other)
+        }
+    }
+
+    fn signum(&self) -> Self {
+        match (Mpi::is_negative(self), Mpi::is_zero(self)) {
+            (true, _) => Mpi::from_int(-1),
+            (_, true) => Mpi::new(),
+            _ => Mpi::from_int(1),
+        }
+    }
+
+    fn is_positive(&self) -> bool {
+        !Mpi::is_negative(self) && !Mpi::is_zero(self)
+    }
+
+    fn is_negative(&self) -> bool {
+        Mpi::is_negative(self)
+    }
+}
+
+macro_rules! impl_pow {
+    ($($exp:ty),*) => {$(
+        impl Pow<$exp> for Mpi {
+            type Output = Mpi;
+
+            fn pow(self, rhs: $exp) -> Self::Output {
+                Mpi::pow(&self, rhs as u64)
+            }
+        }
+
+        impl Pow<$exp> for &Mpi {
+            type Output = Mpi;
+
+            fn pow(self, rhs: $exp) -> Self::Output {
+                Mpi::pow(self, rhs as u64)
+            }
+        }
+    )*};
+}
+
+impl_pow!(u8, u16, u32, u64, usize);
+
+impl ToPrimitive for Mpi {
+    fn to_i64(&self) -> Option<i64> {
+        i64::try_from(self).ok()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        u64::try_from(self).ok()
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        let abs = Mpi::abs(self).to_u128()?;
+        match Mpi::is_negative(self) {
+            true if abs <= 1 << 127 => Some((abs as i128).wrapping_neg()),
+            false if abs < 1 << 127 => Some(abs as i128),
+            _ => None,
+        }
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        if Mpi::is_negative(self) {
+            return None;
+        }
+        let digits = self.digits();
+        let used = digits.iter().rposition(|&d| d != 0).map_or(0, |i| i + 1);
+        if used > 2 {
+            return None;
+        }
+        Some(
+            digits[..used]
+                .iter()
+                .rev()
+                .fold(0, |acc, &d| (acc << 64) | d as u128),
+        )
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.to_str_radix(10).parse().ok()
+    }
+}
+
+impl FromPrimitive for Mpi {
+    fn from_i64(n: i64) -> Option<Self> {
+        Some(Mpi::from(n))
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        Some(Mpi::from(n))
+    }
+
+    fn from_i128(n: i128) -> Option<Self> {
+        let abs = Mpi::from_bytes_be(&n.unsigned_abs().to_be_bytes());
+        Some(if n < 0 { Mpi::neg(&abs) } else { abs })
+    }
+
+    fn from_u128(n: u128) -> Option<Self> {
+        Some(Mpi::from_bytes_be(&n.to_be_bytes()))
+    }
+}
+
+impl From<&BigInt> for Mpi {
+    fn from(value: &BigInt) -> Self {
+        let (sign, bytes) = value.to_bytes_be();
+        let abs = Mpi::from_bytes_be(&bytes);
+        match sign {
+            Sign::Minus => Mpi::neg(&abs),
+            _ => abs,
+        }
+    }
+}
+
+impl From<BigInt> for Mpi {
+    fn from(value: BigInt) -> Self {
+        Mpi::from(&value)
+    }
+}
+
+impl From<&BigUint> for Mpi {
+    fn from(value: &BigUint) -> Self {
+        Mpi::from_bytes_be(&value.to_bytes_be())
+    }
+}
+
+impl From<BigUint> for Mpi {
+    fn from(value: BigUint) -> Self {
+        Mpi::from(&value)
+    }
+}
+
+impl From<&Mpi> for BigInt {
+    fn from(value: &Mpi) -> Self {
+        let sign = if value.is_negative() {
+            Sign::Minus
+        } else {
+            Sign::Plus
+        };
+        BigInt::from_bytes_be(sign, &value.to_bytes_be())
+    }
+}
+
+impl From<Mpi> for BigInt {
+    fn from(value: Mpi) -> Self {
+        BigInt::from(&value)
+    }
+}
+
+impl TryFrom<&Mpi> for BigUint {
+    type Error = String;
+
+    fn try_from(value: &Mpi) -> Result<Self, Self::Error> {
+        if value.is_negative() {
+            return Err("Negative value".into());
+        }
+        Ok(BigUint::from_bytes_be(&value.to_bytes_be()))
+    }
+}
+
+impl TryFrom<Mpi> for BigUint {
+    type Error = String;
+
+    fn try_from(value: Mpi) -> Result<Self, Self::Error> {
+        BigUint::try_from(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // The same random value built independently as `Mpi` and as `BigInt`.
+    fn value(max_bytes: usize) -> impl Strategy<Value = (Mpi, BigInt)> {
+        (
+            any::<bool>(),
+            prop::collection::vec(any::<u8>(), 0..max_bytes),
+        )
+            .prop_map(|(negative, bytes)| {
+                let abs = Mpi::from_bytes_be(&bytes);
+                let big = BigInt::from_bytes_be(Sign::Plus, &bytes);
+                if negative {
+                    (Mpi::neg(&abs), -big)
+                } else {
+                    (abs, big)
+                }
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn conversions((a, big) in value(96)) {
+            prop_assert_eq!(BigInt::from(&a), big.clone());
+            prop_assert_eq!(Mpi::from(&big), a.clone());
+            prop_assert_eq!(BigUint::try_from(&a).is_ok(), !big.is_negative());
+            prop_assert_eq!(Mpi::from(big.magnitude()), Mpi::abs(&a));
+        }
+
+        #[test]
+        fn arithmetic((a, x) in value(96), (b, y) in value(96)) {
+            prop_assert_eq!(BigInt::from(&a + &b), &x + &y);
+            prop_assert_eq!(BigInt::from(&a - &b), &x - &y);
+            prop_assert_eq!(BigInt::from(&a * &b), &x * &y);
+            prop_assert_eq!(a.cmp(&b), x.cmp(&y));
+        }
+
+        #[test]
+        fn division((a, x) in value(96), (b, y) in value(48)) {
+            prop_assume!(!y.is_zero());
+
+            prop_assert_eq!(BigInt::from(&a / &b), &x / &y);
+            prop_assert_eq!(BigInt::from(&a % &b), &x % &y);
+            let (q, r) = a.div_rem(&b);
+            prop_assert_eq!((BigInt::from(q), BigInt::from(r)), (&x / &y, &x % &y));
+        }
+
+        #[test]
+        fn str_radix((a, x) in value(48), radix in 2u32..=36) {
+            let s = x.to_str_radix(radix);
+            prop_assert_eq!(<Mpi as Num>::from_str_radix(&s, radix).unwrap(), a.clone());
+            prop_assert_eq!(a.to_str_radix(radix), s);
+        }
+
+        #[test]
+        fn pow((a, x) in value(24), exp in 0u32..24) {
+            prop_assert_eq!(BigInt::from(Pow::pow(&a, exp)), Pow::pow(&x, exp));
+        }
+
+        #[test]
+        fn signed((a, x) in value(32), (b, y) in value(32)) {
+            prop_assert_eq!(BigInt::from(Signed::abs(&a)), x.abs());
+            prop_assert_eq!(BigInt::from(a.signum()), x.signum());
+            prop_assert_eq!(BigInt::from(a.abs_sub(&b)), x.abs_sub(&y));
+            prop_assert_eq!(a.is_positive(), x.is_positive());
+        }
+
+        #[test]
+        fn primitives((a, x) in value(20), n in any::<i128>(), u in any::<u128>()) {
+            prop_assert_eq!(a.to_i64(), x.to_i64());
+            prop_assert_eq!(a.to_u64(), x.to_u64());
+            prop_assert_eq!(a.to_i128(), x.to_i128());
+            prop_assert_eq!(a.to_u128(), x.to_u128());
+            prop_assert_eq!(Mpi::from_i128(n).unwrap().to_i128(), Some(n));
+            prop_assert_eq!(Mpi::from_u128(u).unwrap().to_u128(), Some(u));
+        }
+
+        #[test]
+        fn sum_product(values in prop::collection::vec(value(16), 0..8)) {
+            let sum: BigInt = values.iter().map(|(_, x)| x).sum();
+            let product: BigInt = values.iter().map(|(_, x)| x).product();
+
+            prop_assert_eq!(BigInt::from(values.iter().map(|(a, _)| a).sum::<Mpi>()), sum);
+            prop_assert_eq!(BigInt::from(values.iter().map(|(a, _)| a).product::<Mpi>()), product);
+        }
+    }
+
+    #[test]
+    fn num_traits() {
+        assert!(<Mpi as Zero>::zero().is_zero());
+        assert_eq!(<Mpi as One>::one(), Mpi::from(1i64));
+        assert_eq!(
+            <Mpi as Num>::from_str_radix("-ff", 16),
+            Ok(Mpi::from(-255i64))
+        );
+        assert_eq!(
+            <Mpi as Num>::from_str_radix("-Zz", 36),
+            Ok(Mpi::from(-1295i64))
+        );
+        assert!(<Mpi as Num>::from_str_radix("z", 35).is_err());
+        assert!(<Mpi as Num>::from_str_radix("1", 37).is_err());
+        assert_eq!(Mpi::from(1i64).shl(200).to_f64(), Some(2f64.powi(200)));
+        assert_eq!(Mpi::from(i64::MIN).to_i128(), Some(i64::MIN as i128));
+        assert_eq!(
+            Mpi::from_i128(i128::MIN).unwrap().to_i128(),
+            Some(i128::MIN)
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use super::Mpi;
+use core::{
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign},
+};
+use cry_sys::bindings::cry_mpi_mul_dig;
+
+impl Mpi {
+    /// Multiplication by a single digit.
+    pub fn mul_u64(&self, other: u64) -> Self {
+        let mut res = Mpi::new();
+        checked!(cry_mpi_mul_dig(&mut res.backend, &self.backend, other));
+        res
+    }
+
+    pub fn mul_i64(&self, other: i64) -> Self {
+        let res = self.mul_u64(other.unsigned_abs());
+        if other < 0 {
+            Mpi::neg(&res)
+        } else {
+            res
+        }
+    }
+}
+
+// Operators on references, forwarding to the inherent methods.
+macro_rules! impl_ref_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+        impl $op<&Mpi> for Mpi {
+            type Output = Mpi;
+
+            fn $fn(self, rhs: &Mpi) -> Self::Output {
+                Mpi::$fn(&self, rhs)
+            }
+        }
+
+        impl $op<&Mpi> for &Mpi {
+            type Output = Mpi;
+
+            fn $fn(self, rhs: &Mpi) -> Self::Output {
+                Mpi::$fn(self, rhs)
+            }
+        }
+
+        impl $op<Mpi> for &Mpi {
+            type Output = Mpi;
+
+            fn $fn(self, rhs: Mpi) -> Self::Output {
+                Mpi::$fn(self, &rhs)
+            }
+        }
+
+        impl $op_assign<&Mpi> for Mpi {
+            fn $fn_assign(&mut self, rhs: &Mpi) {
+                Mpi::$fn_assign(self, rhs);
+            }
+        }
+    };
+}
+
+impl_ref_op!(Add, add, AddAssign, add_assign);
+impl_ref_op!(Sub, sub, SubAssign, sub_assign);
+impl_ref_op!(Mul, mul, MulAssign, mul_assign);
+impl_ref_op!(Div, div, DivAssign, div_assign);
+impl_ref_op!(Rem, rem, RemAssign, rem_assign);
+
+// Operators with a primitive integer right-hand side.
+macro_rules! impl_prim_op {
+    ($prim:ty, $op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+        impl $op<$prim> for Mpi {
+            type Output = Mpi;
+
+            fn $fn(self, rhs: $prim) -> Self::Output {
+                Mpi::$fn(&self, &Mpi::from(rhs))
+            }
+        }
+
+        impl $op<$prim> for &Mpi {
+            type Output = Mpi;
+
+            fn $fn(self, rhs: $prim) -> Self::Output {
+                Mpi::$fn(self, &Mpi::from(rhs))
+            }
+        }
+
+        impl $op_assign<$prim> for Mpi {
+            fn $fn_assign(&mut self, rhs: $prim) {
+                Mpi::$fn_assign(self, &Mpi::from(rhs));
+            }
+        }
+    };
+}
+
+impl_prim_op!(u64, Add, add, AddAssign, add_assign);
+impl_prim_op!(u64, Sub, sub, SubAssign, sub_assign);
+impl_prim_op!(u64, Div, div, DivAssign, div_assign);
+impl_prim_op!(u64, Rem, rem, RemAssign, rem_assign);
+impl_prim_op!(i64, Add, add, AddAssign, add_assign);
+impl_prim_op!(i64, Sub, sub, SubAssign, sub_assign);
+impl_prim_op!(i64, Div, div, DivAssign, div_assign);
+impl_prim_op!(i64, Rem, rem, RemAssign, rem_assign);
+
+// Multiplication by a primitive goes through the single digit fast path.
+macro_rules! impl_prim_mul {
+    ($prim:ty, $mul_fn:ident) => {
+        impl Mul<$prim> for Mpi {
+            type Output = Mpi;
+
+            fn mul(self, rhs: $prim) -> Self::Output {
+                Mpi::$mul_fn(&self, rhs)
+            }
+        }
+
+        impl Mul<$prim> for &Mpi {
+            type Output = Mpi;
+
+            fn mul(self, rhs: $prim) -> Self::Output {
+                Mpi::$mul_fn(self, rhs)
+            }
+        }
+
+        impl MulAssign<$prim> for Mpi {
+            fn mul_assign(&mut self, rhs: $prim) {
+                *self = Mpi::$mul_fn(self, rhs);
+            }
+        }
+    };
+}
+
+impl_prim_mul!(u64, mul_u64);
+impl_prim_mul!(i64, mul_i64);
+
+impl Sum for Mpi {
+    fn sum<I: Iterator<Item = Mpi>>(iter: I) -> Self {
+        iter.fold(Mpi::new(), |acc, x| acc + &x)
+    }
+}
+
+impl<'a> Sum<&'a Mpi> for Mpi {
+    fn sum<I: Iterator<Item = &'a Mpi>>(iter: I) -> Self {
+        iter.fold(Mpi::new(), |acc, x| acc + x)
+    }
+}
+
+impl Product for Mpi {
+    fn product<I: Iterator<Item = Mpi>>(iter: I) -> Self {
+        iter.fold(Mpi::from(1i64), |acc, x| acc * &x)
+    }
+}
+
+impl<'a> Product<&'a Mpi> for Mpi {
+    fn product<I: Iterator<Item = &'a Mpi>>(iter: I) -> Self {
+        iter.fold(Mpi::from(1i64), |acc, x| acc * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A_HEX: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68e1f9";
+    const B_HEX: &str = "6ba218432ef91f921a3dfe1b19725e61274cf66f76b5297c6ccd95c0d3d20ac1";
+
+    const ADD_RES: &str = "153d51542d57afe59bc6593d5a7c4ec3710fc5388f6062660a0d49db0c03aecba";
+    const MUL_RES: &str = "61a05604ac0af481e2141fb88a9a3958d46bf7925dd4273091e10b1be8d4e6b41805b66838d5759f1cdb7289b546945da92482d639d088db1cdb2326ed2816b9";
+    const REM_RES: &str = "10eecc79488f9fa36dab99845b6dd1139b15703a91e6a9eb5a6bdc6e44c4cc77";
+
+    const MUL_U64_RES: &str =
+        "e832fcffa681debf785cb0bdb3c1b8d1364b198a7e6a005efcdcc20a728ffdf32bc0b890b0500e3f";
+    const REM_U64_RES: &str = "f2ea21dd96342461";
+
+    fn from_hex(s: &str) -> Mpi {
+        Mpi::from_hex(s).unwrap()
+    }
+
+    #[test]
+    fn ref_ops() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+
+        assert_eq!(format!("{:x}", &a + &b), ADD_RES);
+        assert_eq!(format!("{:x}", &a * b.clone()), MUL_RES);
+        assert_eq!(format!("{:x}", a.clone() % &b), REM_RES);
+        assert_eq!(&(&a - &b) + &b, a);
+        assert_eq!(&(&a * &b) / &a, b);
+    }
+
+    #[test]
+    fn ref_assign_ops() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+        let mut c = a.clone();
+
+        c += &b;
+        assert_eq!(format!("{:x}", c), ADD_RES);
+
+        c -= &b;
+        c *= &b;
+        assert_eq!(format!("{:x}", c), MUL_RES);
+
+        c /= &b;
+        c %= &b;
+        assert_eq!(format!("{:x}", c), REM_RES);
+    }
+
+    #[test]
+    fn prim_ops() {
+        let a = from_hex(A_HEX);
+        let x = 0xfffffffffffffff7u64;
+
+        assert_eq!(format!("{:x}", &a * x), MUL_U64_RES);
+        assert_eq!(format!("{:x}", &a % x), REM_U64_RES);
+        assert_eq!(&a * x, &a * &Mpi::from(x));
+        assert_eq!(&a * -5i64, -(&a * 5u64));
+        assert_eq!(&(&a + 7u64) - 7i64, a);
+        assert_eq!(&(&a / x) * x + (&a % x), a);
+    }
+
+    #[test]
+    fn prim_assign_ops() {
+        let a = from_hex(A_HEX);
+        let mut c = a.clone();
+
+        c *= 3u64;
+        c += 1i64;
+        c -= 1u64;
+        c /= 3i64;
+
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn sum_product() {
+        let values: Vec<Mpi> = [3i64, -5, 7].iter().map(|&v| Mpi::from(v)).collect();
+
+        assert_eq!(values.iter().sum::<Mpi>(), Mpi::from(5i64));
+        assert_eq!(values.iter().product::<Mpi>(), Mpi::from(-105i64));
+        assert_eq!(values.into_iter().sum::<Mpi>(), Mpi::from(5i64));
+        assert_eq!(core::iter::empty::<Mpi>().product::<Mpi>(), Mpi::from(1i64));
+    }
+}
@@ -0,0 +1,303 @@
+use super::{rand::random_bytes, Mpi};
+use cry_sys::bindings::cry_mpi_is_prime;
+use rand_core::{CryptoRng, RngCore};
+
+// Odd primes used by the sieve and by the trial division pre-check.
+const SMALL_PRIMES: [u32; 167] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251, 257, 263, 269, 271, 277, 281, 283, 293, 307,
+    311, 313, 317, 331, 337, 347, 349, 353, 359, 367, 373, 379, 383, 389, 397, 401, 409, 419, 421,
+    431, 433, 439, 443, 449, 457, 461, 463, 467, 479, 487, 491, 499, 503, 509, 521, 523, 541, 547,
+    557, 563, 569, 571, 577, 587, 593, 599, 601, 607, 613, 617, 619, 631, 641, 643, 647, 653, 659,
+    661, 673, 677, 683, 691, 701, 709, 719, 727, 733, 739, 743, 751, 757, 761, 769, 773, 787, 797,
+    809, 811, 821, 823, 827, 829, 839, 853, 857, 859, 863, 877, 881, 883, 887, 907, 911, 919, 929,
+    937, 941, 947, 953, 967, 971, 977, 983, 991, 997,
+];
+
+// Numbers without factors in `SMALL_PRIMES` and below this bound (997^2) are prime.
+const SMALL_PRIMES_BOUND: u64 = 994009;
+
+// Below this size candidates may be equal to one of the sieving primes.
+const SIEVE_MIN_BITS: usize = 11;
+
+// Candidates examined from a random starting point before drawing a new one.
+const SIEVE_MAX_DELTA: u32 = 1 << 16;
+
+/// Miller-Rabin rounds giving an error probability below 2^-80 for a random
+/// candidate of the given size (HAC, table 4.4).
+fn miller_rabin_rounds(bits: usize) -> usize {
+    match bits {
+        1300.. => 2,
+        850.. => 3,
+        650.. => 4,
+        550.. => 5,
+        450.. => 6,
+        400.. => 7,
+        350.. => 8,
+        300.. => 9,
+        250.. => 12,
+        200.. => 15,
+        150.. => 18,
+        _ => 27,
+    }
+}
+
+// Returns `Some` if primality is settled by trial division, `None` otherwise.
+fn trial_division(n: &Mpi) -> Option<bool> {
+    let small = match n.digits() {
+        [] => return Some(false),
+        [d] => Some(*d),
+        _ => None,
+    };
+    if n.is_negative() || small == Some(1) {
+        return Some(false);
+    }
+    if !n.is_odd() {
+        return Some(small == Some(2));
+    }
+    for &p in SMALL_PRIMES.iter() {
+        if n.rem_u32(p) == 0 {
+            return Some(small == Some(p as u64));
+        }
+    }
+    match small {
+        Some(d) if d < SMALL_PRIMES_BOUND => Some(true),
+        _ => None,
+    }
+}
+
+// Random odd number with exactly `bits` bits and the two most significant set.
+fn random_odd<R: RngCore + CryptoRng>(bits: usize, rng: &mut R) -> Mpi {
+    let mut buf = random_bytes(bits, rng);
+    let len = buf.len();
+    let mut set_bit = |i: usize| buf[len - 1 - i / 8] |= 1 << (i % 8);
+    set_bit(bits - 1);
+    set_bit(bits.saturating_sub(2));
+    set_bit(0);
+    Mpi::from_bytes_be(&buf)
+}
+
+// Walks `start`, `start + 2`, `start + 4`, ... skipping the candidates for which
+// `excluded(candidate mod p, p)` holds for some small prime `p`, and returns the
+// first one accepted by `test`. Gives up when the candidates outgrow `bits`.
+fn sieve_search(
+    start: &Mpi,
+    bits: usize,
+    excluded: impl Fn(u32, u32) -> bool,
+    mut test: impl FnMut(&Mpi) -> bool,
+) -> Option<Mpi> {
+    let residues: Vec<u32> = SMALL_PRIMES.iter().map(|&p| start.rem_u32(p)).collect();
+    let mut delta = 0;
+    while delta < SIEVE_MAX_DELTA {
+        let skip = SMALL_PRIMES
+            .iter()
+            .zip(residues.iter())
+            .any(|(&p, &r)| excluded((r + delta) % p, p));
+        if !skip {
+            let candidate = start.add(&Mpi::from_int(delta as i64));
+            if candidate.bits_count() != bits {
+                return None;
+            }
+            if test(&candidate) {
+                return Some(candidate);
+            }
+        }
+        delta += 2;
+    }
+    None
+}
+
+impl Mpi {
+    /// Primality check performed by the backend.
+    pub fn is_prime(&self) -> bool {
+        unsafe { cry_mpi_is_prime(&self.backend) > 0 }
+    }
+
+    /// Probabilistic primality test.
+    ///
+    /// Small factors are first ruled out by trial division, then `rounds`
+    /// Miller-Rabin iterations are run using bases drawn from `rng`.
+    /// A composite number passes with probability at most `4^-rounds`.
+    pub fn is_probable_prime<R: RngCore + CryptoRng>(&self, rounds: usize, rng: &mut R) -> bool {
+        if let Some(res) = trial_division(self) {
+            return res;
+        }
+        let one = Mpi::from_int(1);
+        let two = Mpi::from_int(2);
+        let n_minus_one = self.sub(&one);
+        let s = n_minus_one.trailing_zeros();
+        let d = n_minus_one.shr(s);
+        // Bases are uniform in [2, n - 2]
+        let range = self.sub(&Mpi::from_int(3));
+
+        'rounds: for _ in 0..rounds {
+            let a = Mpi::random_below(&range, rng).add(&two);
+            let mut x = a.mod_exp(&d, self);
+            if x == one || x == n_minus_one {
+                continue;
+            }
+            for _ in 1..s {
+                x = x.mul(&x).rem(self);
+                if x == n_minus_one {
+                    continue 'rounds;
+                }
+                if x == one {
+                    return false;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    /// Generates a random prime with exactly `bits` bits.
+    ///
+    /// The two most significant bits are set, thus the product of two primes
+    /// generated with this function has exactly `2 * bits` bits.
+    /// The result is fully determined by the `rng` output.
+    pub fn random_prime<R: RngCore + CryptoRng>(bits: usize, rng: &mut R) -> Self {
+        assert!(bits >= 2, "A prime has at least 2 bits");
+        let rounds = miller_rabin_rounds(bits);
+        loop {
+            let start = random_odd(bits, rng);
+            if bits < SIEVE_MIN_BITS {
+                if start.is_probable_prime(rounds, rng) {
+                    return start;
+                }
+                continue;
+            }
+            let found = sieve_search(
+                &start,
+                bits,
+                |r, _| r == 0,
+                |c| c.is_probable_prime(rounds, rng),
+            );
+            if let Some(p) = found {
+                return p;
+            }
+        }
+    }
+
+    /// Generates a random safe prime `p = 2q + 1`, with `q` prime, with exactly
+    /// `bits` bits.
+    ///
+    /// As for `random_prime`, the two most significant bits are set.
+    pub fn random_safe_prime<R: RngCore + CryptoRng>(bits: usize, rng: &mut R) -> Self {
+        assert!(bits >= 3, "A safe prime has at least 3 bits");
+        let rounds = miller_rabin_rounds(bits);
+        let one = Mpi::from_int(1);
+        let safe = |q: &Mpi| q.add(q).add(&one);
+        loop {
+            let start = random_odd(bits - 1, rng);
+            let mut test = |q: &Mpi| {
+                q.is_probable_prime(rounds, rng) && safe(q).is_probable_prime(rounds, rng)
+            };
+            if bits - 1 < SIEVE_MIN_BITS {
+                if test(&start) {
+                    return safe(&start);
+                }
+                continue;
+            }
+            // Sieve both `q` and `2q + 1`: the latter is divisible by `p`
+            // exactly when `q mod p = (p - 1) / 2`.
+            let found = sieve_search(
+                &start,
+                bits - 1,
+                |r, p| r == 0 || r == (p - 1) / 2,
+                &mut test,
+            );
+            if let Some(q) = found {
+                return safe(&q);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // 2^127 - 1
+    const MERSENNE_127: &str = "7fffffffffffffffffffffffffffffff";
+    // 2^128 + 1 (F7), composite
+    const FERMAT_7: &str = "100000000000000000000000000000001";
+    // Chernick's Carmichael number (6k + 1)(12k + 1)(18k + 1) with k = 1000051
+    const CARMICHAEL: &str = "46445ed4c703fc1749";
+    // 151 * 751 * 28351, strong pseudoprime to bases 2, 3, 5 and 7
+    const STRONG_PSEUDOPRIME: &str = "bfa17dc7";
+
+    fn from_hex(s: &str) -> Mpi {
+        Mpi::from_hex(s).unwrap()
+    }
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::seed_from_u64(42)
+    }
+
+    #[test]
+    fn small_numbers() {
+        let mut rng = rng();
+        let primes = [2, 3, 5, 997, 1009, 65537];
+        let composites = [0, 1, 4, 561, 1001, 65535];
+
+        for p in primes {
+            assert!(Mpi::from_int(p).is_probable_prime(10, &mut rng));
+        }
+        for c in composites {
+            assert!(!Mpi::from_int(c).is_probable_prime(10, &mut rng));
+        }
+        assert!(!Mpi::from_int(-7).is_probable_prime(10, &mut rng));
+    }
+
+    #[test]
+    fn is_probable_prime() {
+        let mut rng = rng();
+
+        assert!(from_hex(MERSENNE_127).is_probable_prime(20, &mut rng));
+        assert!(!from_hex(FERMAT_7).is_probable_prime(20, &mut rng));
+        assert!(!from_hex(CARMICHAEL).is_probable_prime(20, &mut rng));
+        assert!(!from_hex(STRONG_PSEUDOPRIME).is_probable_prime(20, &mut rng));
+    }
+
+    #[test]
+    fn is_prime() {
+        assert!(from_hex(MERSENNE_127).is_prime());
+        assert!(!from_hex(FERMAT_7).is_prime());
+    }
+
+    #[test]
+    fn random_prime() {
+        let mut rng = rng();
+
+        for bits in [2, 8, 64, 256, 512] {
+            let p = Mpi::random_prime(bits, &mut rng);
+
+            assert_eq!(p.bits_count(), bits);
+            assert!(p.is_probable_prime(20, &mut rng));
+        }
+    }
+
+    #[test]
+    fn random_prime_is_reproducible() {
+        let p1 = Mpi::random_prime(256, &mut rng());
+        let p2 = Mpi::random_prime(256, &mut rng());
+
+        assert_eq!(p1, p2);
+    }
+
+    #[test]
+    fn random_safe_prime() {
+        let mut rng = rng();
+
+        for bits in [5, 64, 256] {
+            let p = Mpi::random_safe_prime(bits, &mut rng);
+            let q = p.shr(1);
+
+            assert_eq!(p.bits_count(), bits);
+            assert!(p.is_probable_prime(20, &mut rng));
+            assert!(q.is_probable_prime(20, &mut rng));
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use super::Mpi;
+use cry_sys::bindings::{cry_mpi_rand, cry_mpi_rand_range};
+use rand_core::{CryptoRng, RngCore};
+
+// Big-endian random bytes holding a value of at most `bits` bits.
+pub(super) fn random_bytes<R: RngCore + CryptoRng>(bits: usize, rng: &mut R) -> Vec<u8> {
+    let len = bits.div_ceil(8);
+    let mut buf = vec![0; len];
+    rng.fill_bytes(&mut buf);
+    if let Some(msb) = buf.first_mut() {
+        *msb &= 0xff >> (8 * len - bits);
+    }
+    buf
+}
+
+impl Mpi {
+    /// Uniformly random non-negative number in the range `[0, 2^bits)`.
+    pub fn random_bits<R: RngCore + CryptoRng>(bits: usize, rng: &mut R) -> Self {
+        Mpi::from_bytes_be(&random_bytes(bits, rng))
+    }
+
+    /// Uniformly random non-negative number in the range `[0, max)`.
+    ///
+    /// Values are drawn by rejection sampling, thus there is no modulo bias.
+    ///
+    /// Panics if `max` is not positive.
+    pub fn random_below<R: RngCore + CryptoRng>(max: &Mpi, rng: &mut R) -> Self {
+        assert!(!max.is_zero() && !max.is_negative(), "Empty range");
+        let bits = max.bits_count();
+        loop {
+            let r = Mpi::random_bits(bits, rng);
+            if &r < max {
+                return r;
+            }
+        }
+    }
+
+    /// Random number with at most `bits` bits drawn from the backend internal
+    /// PRNG.
+    pub fn backend_random_bits(bits: usize) -> Self {
+        let mut res = Mpi::new();
+        checked!(cry_mpi_rand(&mut res.backend, bits as u64));
+        res
+    }
+
+    /// Random number in the range `[0, max)` drawn from the backend internal
+    /// PRNG.
+    ///
+    /// Panics if `max` is not positive.
+    pub fn backend_random_below(max: &Mpi) -> Self {
+        assert!(!max.is_zero() && !max.is_negative(), "Empty range");
+        let mut res = Mpi::new();
+        checked!(cry_mpi_rand_range(&mut res.backend, &max.backend));
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    const MAX_HEX: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68e1f9";
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::seed_from_u64(42)
+    }
+
+    #[test]
+    fn random_bits() {
+        let mut rng = rng();
+
+        for bits in [0, 1, 7, 8, 9, 64, 65, 255] {
+            let r = Mpi::random_bits(bits, &mut rng);
+
+            assert!(r.bits_count() <= bits);
+            assert!(!r.is_negative());
+        }
+    }
+
+    #[test]
+    fn random_bits_is_reproducible() {
+        let r1 = Mpi::random_bits(256, &mut rng());
+        let r2 = Mpi::random_bits(256, &mut rng());
+
+        assert_eq!(r1, r2);
+    }
+
+    #[test]
+    fn random_below() {
+        let mut rng = rng();
+        let max = Mpi::from_hex(MAX_HEX).unwrap();
+
+        for _ in 0..100 {
+            let r = Mpi::random_below(&max, &mut rng);
+
+            assert!(r < max);
+            assert!(!r.is_negative());
+        }
+    }
+
+    #[test]
+    fn random_below_is_uniform() {
+        let mut rng = rng();
+        let max = Mpi::from_int(5);
+        let mut hits = [0; 5];
+
+        for _ in 0..5000 {
+            let r = Mpi::random_below(&max, &mut rng);
+            hits[r.rem_u32(5) as usize] += 1;
+        }
+
+        assert!(hits.iter().all(|&h| (900..1100).contains(&h)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_below_zero() {
+        Mpi::random_below(&Mpi::new(), &mut rng());
+    }
+
+    #[test]
+    fn backend_random() {
+        let max = Mpi::from_hex(MAX_HEX).unwrap();
+
+        let r = Mpi::backend_random_bits(100);
+        assert!(r.bits_count() <= 100);
+
+        let r = Mpi::backend_random_below(&max);
+        assert!(r < max);
+    }
+}
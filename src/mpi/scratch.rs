@@ -0,0 +1,148 @@
+use super::Mpi;
+use core::mem::MaybeUninit;
+use cry_sys::bindings::cry_mpi_init_size;
+
+impl Mpi {
+    /// Zero value with storage pre-allocated for `digits` digits.
+    pub fn with_capacity(digits: usize) -> Self {
+        let mut backend = MaybeUninit::uninit();
+        checked!(cry_mpi_init_size(backend.as_mut_ptr(), digits as u64));
+        Mpi {
+            backend: unsafe { backend.assume_init() },
+        }
+    }
+
+    /// Number of digits that can be stored without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.backend.alloc as usize
+    }
+}
+
+/// Pool of pre-sized temporaries.
+///
+/// Values are borrowed with `take` and handed back with `put`, so that their
+/// storage is reused by the following iterations. Combined with the `*_into`
+/// operations this allows loops to run without allocating once the pool is
+/// warm. Values handed back to the pool are not cleared.
+pub struct Scratch {
+    free: Vec<Mpi>,
+    digits: usize,
+}
+
+impl Scratch {
+    /// New pool whose fresh values have room for `digits` digits.
+    pub fn new(digits: usize) -> Self {
+        Scratch {
+            free: Vec::new(),
+            digits,
+        }
+    }
+
+    /// New pool pre-filled with `count` values.
+    pub fn with_values(digits: usize, count: usize) -> Self {
+        let mut this = Scratch::new(digits);
+        this.free
+            .extend((0..count).map(|_| Mpi::with_capacity(digits)));
+        this
+    }
+
+    /// Borrows a value from the pool, allocating a new one if empty.
+    pub fn take(&mut self) -> Mpi {
+        self.free
+            .pop()
+            .unwrap_or_else(|| Mpi::with_capacity(self.digits))
+    }
+
+    /// Hands a value back to the pool.
+    pub fn put(&mut self, value: Mpi) {
+        self.free.push(value);
+    }
+
+    /// Number of values available without allocating.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const A_HEX: &str = "e832fcffa681dec7a22795ba8e528dd5e9af5d197f50fce4340707efec68e1f9";
+    const B_HEX: &str = "6ba218432ef91f921a3dfe1b19725e61274cf66f76b5297c6ccd95c0d3d20ac1";
+    const C_HEX: &str = "4ca6087f5d5f081fbe7a305def082458ae2c5020b2b6116253dc62a8beab09ac";
+
+    fn from_hex(s: &str) -> Mpi {
+        Mpi::from_hex(s).unwrap()
+    }
+
+    #[test]
+    fn with_capacity() {
+        let a = Mpi::with_capacity(64);
+
+        assert!(a.capacity() >= 64);
+        assert!(a.is_zero());
+    }
+
+    #[test]
+    fn into_ops() {
+        let a = from_hex(A_HEX);
+        let b = from_hex(B_HEX);
+        let c = from_hex(C_HEX);
+        let mut dst = Mpi::with_capacity(16);
+        let mut rem = Mpi::with_capacity(16);
+
+        a.add_into(&b, &mut dst);
+        assert_eq!(dst, a.add(&b));
+        a.sub_into(&b, &mut dst);
+        assert_eq!(dst, a.sub(&b));
+        a.mul_into(&b, &mut dst);
+        assert_eq!(dst, a.mul(&b));
+        a.square_into(&mut dst);
+        assert_eq!(dst, a.mul(&a));
+        a.div_into(&c, &mut dst);
+        assert_eq!(dst, a.div(&c));
+        a.rem_into(&c, &mut dst);
+        assert_eq!(dst, a.rem(&c));
+        a.div_rem_into(&c, &mut dst, &mut rem);
+        assert_eq!((dst, rem), a.div_rem(&c));
+
+        let mut dst = Mpi::new();
+        a.mod_exp_into(&b, &c, &mut dst);
+        assert_eq!(dst, a.mod_exp(&b, &c));
+    }
+
+    #[test]
+    fn scratch_reuses_values() {
+        let mut scratch = Scratch::with_values(8, 2);
+        assert_eq!(scratch.available(), 2);
+
+        let t = scratch.take();
+        let data = t.backend.data;
+        assert!(t.capacity() >= 8);
+        scratch.put(t);
+
+        let t = scratch.take();
+        assert_eq!(t.backend.data, data);
+        scratch.put(t);
+        assert_eq!(scratch.available(), 2);
+    }
+
+    #[test]
+    fn scratch_loop() {
+        let a = from_hex(A_HEX);
+        let m = from_hex(C_HEX);
+        let mut scratch = Scratch::new(2 * a.digits().len());
+        let mut acc = Mpi::from(1u64);
+
+        for _ in 0..16 {
+            let mut t = scratch.take();
+            acc.mul_into(&a, &mut t);
+            t.rem_into(&m, &mut acc);
+            scratch.put(t);
+        }
+
+        assert_eq!(acc, a.mod_exp(&Mpi::from(16u64), &m));
+        assert_eq!(scratch.available(), 1);
+    }
+}
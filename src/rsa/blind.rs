@@ -0,0 +1,337 @@
+//! RSA blind signatures (RFC 9474).
+//!
+//! The client blinds a message with [`blind`], the signer signs the blinded
+//! message with [`blind_sign`] without learning anything about it, and the
+//! client unblinds the result with [`finalize`], obtaining a standard RSA-PSS
+//! signature which can't be linked to the signing request. The free functions
+//! use the recommended RSABSSA-SHA384-PSS-Randomized variant, the methods of
+//! [`Variant`] support the others.
+//!
+//! The randomized variants prefix the message with 32 random bytes to prevent
+//! the signer from choosing the signed content. The prefixed message returned
+//! by [`Variant::prepare`] must be used for all the following steps and for
+//! verification.
+
+use super::{i2osp, pss::Pss, RsaPrivateKey, RsaPublicKey, SaltLen};
+use crate::{mpi::Mpi, sha512::Sha384, traits::Hasher};
+use core::fmt;
+use rand_core::{CryptoRng, RngCore};
+
+const PREFIX_LEN: usize = 32;
+const SALT_LEN: usize = 48;
+
+/// RFC 9474 protocol variants, all based on SHA-384.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// RSABSSA-SHA384-PSS-Randomized.
+    PssRandomized,
+    /// RSABSSA-SHA384-PSSZERO-Randomized.
+    PssZeroRandomized,
+    /// RSABSSA-SHA384-PSS-Deterministic.
+    PssDeterministic,
+    /// RSABSSA-SHA384-PSSZERO-Deterministic.
+    PssZeroDeterministic,
+}
+
+/// Unblinding factor kept by the client between blinding and finalization.
+pub struct BlindingSecret {
+    inv: Mpi,
+}
+
+impl fmt::Debug for BlindingSecret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BlindingSecret").finish_non_exhaustive()
+    }
+}
+
+impl Variant {
+    fn salt_len(self) -> usize {
+        match self {
+            Variant::PssRandomized | Variant::PssDeterministic => SALT_LEN,
+            Variant::PssZeroRandomized | Variant::PssZeroDeterministic => 0,
+        }
+    }
+
+    fn is_randomized(self) -> bool {
+        matches!(self, Variant::PssRandomized | Variant::PssZeroRandomized)
+    }
+
+    fn pss(self) -> Pss<Sha384> {
+        Pss::with_salt_len(SaltLen::Fixed(self.salt_len()))
+    }
+
+    /// Message to be signed, prefixed with random bytes by the randomized
+    /// variants.
+    pub fn prepare<R: RngCore + CryptoRng>(self, msg: impl AsRef<[u8]>, rng: &mut R) -> Vec<u8> {
+        let mut prepared = Vec::new();
+        if self.is_randomized() {
+            prepared.resize(PREFIX_LEN, 0);
+            rng.fill_bytes(&mut prepared);
+        }
+        prepared.extend_from_slice(msg.as_ref());
+        prepared
+    }
+
+    /// Blinds the prepared message `msg`, returning the blinded message for
+    /// the signer and the secret required to finalize the signature.
+    pub fn blind<R: RngCore + CryptoRng>(
+        self,
+        msg: impl AsRef<[u8]>,
+        key: &RsaPublicKey,
+        rng: &mut R,
+    ) -> Result<(Vec<u8>, BlindingSecret), String> {
+        let mut salt = vec![0; self.salt_len()];
+        rng.fill_bytes(&mut salt);
+        let r = loop {
+            let r = Mpi::random_below(key.n(), rng);
+            if !r.is_zero() {
+                break r;
+            }
+        };
+        self.blind_with(msg.as_ref(), key, &salt, &r)
+    }
+
+    fn blind_with(
+        self,
+        msg: &[u8],
+        key: &RsaPublicKey,
+        salt: &[u8],
+        r: &Mpi,
+    ) -> Result<(Vec<u8>, BlindingSecret), String> {
+        let n = key.n();
+        let digest = Sha384::digest_parts(&[msg]);
+        let em = Pss::<Sha384>::encode(&digest, salt, n.bits_count() - 1)?;
+        let m = Mpi::from_bytes_be(&em);
+        if m.gcd(n) != Mpi::from(1i64) {
+            return Err("Invalid input".into());
+        }
+        let inv = r.mod_inv(n).ok_or("Invalid blind")?;
+        let z = (&m * &key.public_op(r)?) % n;
+        Ok((i2osp(&z, key.size())?, BlindingSecret { inv }))
+    }
+
+    /// Unblinds the signature of the blinded message, returning an RSA-PSS
+    /// signature of the prepared message `msg`.
+    pub fn finalize(
+        self,
+        key: &RsaPublicKey,
+        msg: impl AsRef<[u8]>,
+        blind_sig: impl AsRef<[u8]>,
+        secret: &BlindingSecret,
+    ) -> Result<Vec<u8>, String> {
+        let blind_sig = blind_sig.as_ref();
+        if blind_sig.len() != key.size() {
+            return Err("Unexpected input size".into());
+        }
+        let z = Mpi::from_bytes_be(blind_sig);
+        let s = (&z * &secret.inv) % key.n();
+        let sig = i2osp(&s, key.size())?;
+        if !self.verify(key, msg, &sig) {
+            return Err("Invalid signature".into());
+        }
+        Ok(sig)
+    }
+
+    /// Verifies a finalized signature of the prepared message `msg`.
+    pub fn verify(
+        self,
+        key: &RsaPublicKey,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+    ) -> bool {
+        self.pss().verify(key, msg, signature)
+    }
+}
+
+/// Blinds the message `msg` prepared with [`Variant::PssRandomized`], see
+/// [`Variant::blind`].
+pub fn blind<R: RngCore + CryptoRng>(
+    msg: impl AsRef<[u8]>,
+    key: &RsaPublicKey,
+    rng: &mut R,
+) -> Result<(Vec<u8>, BlindingSecret), String> {
+    Variant::PssRandomized.blind(msg, key, rng)
+}
+
+/// Unblinds a signature of the message `msg` blinded with [`blind`], see
+/// [`Variant::finalize`].
+pub fn finalize(
+    key: &RsaPublicKey,
+    msg: impl AsRef<[u8]>,
+    blind_sig: impl AsRef<[u8]>,
+    secret: &BlindingSecret,
+) -> Result<Vec<u8>, String> {
+    Variant::PssRandomized.finalize(key, msg, blind_sig, secret)
+}
+
+/// Signs a blinded message, `rng` is used to blind the private operation.
+pub fn blind_sign<R: RngCore + CryptoRng>(
+    key: &RsaPrivateKey,
+    blinded: impl AsRef<[u8]>,
+    rng: &mut R,
+) -> Result<Vec<u8>, String> {
+    let blinded = blinded.as_ref();
+    if blinded.len() != key.size() {
+        return Err("Unexpected input size".into());
+    }
+    // The private operation checks the result against the public one.
+    let s = key.private_op(&Mpi::from_bytes_be(blinded), rng)?;
+    i2osp(&s, key.size())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rsa::tests::test_key;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // Intermediate values of the four variants with the `rsa::tests::test_key`
+    // key: prepared message, salt, blinded message, blind signature and
+    // signature, all with the unblinding factor `INV`.
+    //
+    // TODO: add the RFC 9474 Appendix A vectors (4096-bit key), which weren't
+    // at hand. These values were recomputed with an independent Python
+    // implementation and the signatures verify with pyca/cryptography.
+    const MSG: &[u8] = b"privacy pass token";
+    const INV: &str = "efdefb312707b6838de76abcca7ffe1b369600512337ff7d1028488c0f73579c66e2ef916c21100b1192c16e07b4f13ae06dc97dafcc2d56a10475a7cd5ae1fb33f1e17144cb4667e56610be1d9b5102634320fc42e81573e18d1906103cd7c4ba39a4b85127c5add5f4adf458f04e26ae048ed3a4327c0d3e981f40136a8595bbb0ed2c3c231114eeb4490229a2a851ad17ec5bb8d0ae85cfacb6b6e087d6507fe5420a88627c4aafc05f1e06698c39b71af69267b03d4d43b670ebfb97ae281cb06babe8a6aaee29d5a306b32c8927d2c8659295e6d43f36c104e3c920872ab51e4872e1b2402f561e97897f95f94913dad7f55348b9559700dcbf5dbfcea5";
+    const VECTORS: [(Variant, &str, &str, &str, &str, &str); 4] = [
+        (
+            Variant::PssRandomized,
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f70726976616379207061737320746f6b656e",
+            "6465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f808182838485868788898a8b8c8d8e8f90919293",
+            "ca71abdeeac5f09c1557cb26894e6386cb4fa73276b441a39f0c5a00d898a61c2626b36d850539811e13813572545c4b92cdd6b70bfc20c4253c65834bee8899545dcfd6e4e71e28a533260454e776329b174c2b0e87dea91af63919a259d0e4b975fd8916c03ef70355c939757fc05bbc026a0142b02177b1ade0d85ccbdc213985dd563ccf6bcf4087809842f5dcbdb7175550f1bd4e60cdbd7bb6d29ca49dc5abaefe8abfd8f507cbb3f3ba6656927893efd2dcd6375f666ece05069059106e821d34a8b9326f7cd21d82e226078158fde676b41897ff1de04ec3dcc7d1a9f90082bb9eb4ff7f3a0a5340c4544e09824687dd177021a8b0f0b70bb2f6135f",
+            "ac2e28702b2f0a0692d429560f24a29a8975fa9ba1f7338c1d0531a534be8256855357a9b57a78cd75d9cf01721dcf25d0f7b7cca88cec59b470b3fc84181e8127247bf911f991653def1bf1066d3c3bd6c27d65cb86d770a3d058b2f59530580b185bd6e9f01c9680159aa1d88dfa129026e07e9e0661c2022e90627d41ec6c01d29475197c3e1794d28f0ffa3d03d60a0e88cc55f85c34bb9aff78de7e3cd0fbfd8f69efb5ff4ed3db549593f702ab394be15e66b49cbaceca2f2e54f5af88b13b0f3f01ff4b9b9689b6d384df7835c4d04a69f654f70397b7779578b52780e5addd44640c6f0ce7dbfdfc7277818b5f56c49890086762225a30feba03f879",
+            "1ff760a4e6f8246298c01394171ab9e0ac64a0982ecaf83f8deb0b2cddc0d09286dc12d62549226a7f92381e8157fe588736eff20fdd76b3cf5862203392347422e33a966601d18ebf55c46dc307a1d3dd059281cf42a6479ff52bbca6f6a362fc51cbd37ba5be4e63cc328f4841ef8bd901ead33c66c72639510ed73798bb4639fbc67543f6affef14874f28b3d4123e61984167655066bc4f21e09d37b33d774f3cc3d5c1d256eb269cfd42e1d6e40c10ea8364cbd43970e3057f6125ca110b713479e2c0e51e3c9b53b219cfb3f37ccec6bf790b01a9e7967231d213e431694c21aae597f2f02ff0b207c60e085453477f2d9a3fc87de5a2bbbb01fb643e5",
+        ),
+        (
+            Variant::PssZeroRandomized,
+            "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f70726976616379207061737320746f6b656e",
+            "",
+            "4a111e8c64c861cb2dd150cde02545b73d8388dac76bc91380b4629d698e48639c676273d48add71c7a640753bef1dce425b73fec81aef75358c9c742fc4cc30c75561cafdce74d9bd01618593e7c66520f328641ae13be7219680fbf664d0590624f8f373b5a66f4ff5b3d9a1f17cf6af43e30e1d44af37fc27e2107a2448c7855264aca0baefbe84704d8bdb499657107f50b8a9af9c9a4958e565b93fd3b295b58af184afca47962d289a7266cddc14b06e96c0cc45e978f3520b81699887cd150ba3ceb643d9f51a78697e3c2164464cbef43f5a4d82936a21c64c39c0a4c36d46629d52304ec6a5836650e2a255e23e70a3fbe0f3e403128da061de85b6",
+            "832a627ecc9b96d888c5b356b64761a6a3404eb132c35fae9b49a6e278ed2320c384b0171982e726708623ef97d658ed1f22583099eae37f10c8edea273d052c46e41155deb3dfddbe703b17d93ef4cdbcb8ed343d64b23cb13e7e89ac60f0354b71c3572a3ccef1ba3386e608d13a05d8a5f6a2457640dfbd7a9ebaccd5b0be48305f50ce1586c0ccea28aaf53d5e50197b20bebf89017f4d6851f191e62db6d2703e4d835c06ddde2d07fac70026f36cbe9efc358c262f9c37ee7217fa40a69823de33853ab9963d48e7073765a00d887722c0fe7cf7938d1755ad4c5804f5cd27905073fb9863d4711aa40463249370b2139539c64ac6de8a91befa9f580a",
+            "61da400e2287927e9284c3b0e2c4becc614a5ceaab3e1e3ee3b1381af45e02ffa2f5da90f6e2a0d132578803262e3e216832b469da8bbf57a7d842c236ad185ca3392ebc71645249f70a4f0828e8ec40a87a2437673d2145daba0ba4c0f0a63a8cfdd04c2c8320c215bbc495c80501f9cd18c46190fd5cfd247023e6c3422a25892813e3895eed3cbc2b53d7020c468f394534e158440786d6fdfa8926061e5a5565831475aff20e8f3f26a15355796d4d2b2ea21dda8435f7a601eda2d14608ca794ded21ab4384790d073f3a2d994a09441a11b43227bc42b05647ce18e06ab2b7dfa09b13990d0e325e22bcdba78e30726655eba77cd1bc5b2f2ce3c8f683",
+        ),
+        (
+            Variant::PssDeterministic,
+            "70726976616379207061737320746f6b656e",
+            "6465666768696a6b6c6d6e6f707172737475767778797a7b7c7d7e7f808182838485868788898a8b8c8d8e8f90919293",
+            "bf530ef9d869c5f5c24b982348a4540dd96c778eb8214d742f3a22fabd4cfac23b15062f870bdb8d55bbe5ffb5022db633d834dbb547f2ddc0d13006ea7ba3fe4238c5e6b23885f132a3b622dfdf905bc8c4a7b0a72773b77822aebb6cc301d33dd03f40a3e9464d72f7a7c0d1e9e88447a6e9c3541bc72a06b071d60873354603efe601730ef74deaa6c690d1f2fb01f6b66095083075fe3a3cc58c113fb98525f0e248c4ec498c74085154e490fb1bd1816f4cb9c6d4947de9439cd477eb7482bc5f32996537105937c13addfcc896c0efff50d8ac603396c6e4445ed07dca5efe832d0e01494a4e536b27c32366cc6caccef23e949bdd8c58c904a134b78b",
+            "61342d7e595f5348643f3608ecdf558c1730140c2e27dc172c9eb82837d93d29144e2800c5dd667e36f7c8dbe65f32b84e89d4e76c599f390b936ecb1ac532511a3cb96783c8b488de7fbf738fb5c2f3f79af5b8daa8c498d3a355b21e2767a0ade9e38c5da3e7ad80a464e3024a8ee4d0ed1c9dc2b8ad90fa748ee2cf116579ef79a142ce06fffeb8a037453dde112a750bc5ae35596e05f265dfc5695df8ff3d498d9ce4495872297c96692a91c6b54e3a98f81224be19a33df05fc302372be83c89270df0427b417100f9fa10e86f2798f5245eeecfb235a8763a1e0698070265af08caab4c1dc6069f12104ad6a39192d524a57fbc25ee11c314bfbae955",
+            "b3a48a885ccb9720b0793527b889c6c9ccb87661d0e27ebd0e72ab011d6ddc5e4c838806049840995b97e662708b76342f3021ba8303f81169b708852eb6b2d09aa9c5f2f90e2af058bfe592b1d4de4823dff6fafb4e3db065280fe82baade2b2f1696fb4babc48f220621a51bd5168eb18524140e0df82e418902dc1f95a821fcdf233a6167fb8444991323b7673c4de5ac3e8fb97e6f31133f5d46dbbad80b4eaca9b05b4c062dbe65da8f3c76a2494b4d2a8de61f8654475726a780ba20c354d14383c1ae8b762ed5b2706de654c4808a9d92a032bfb173500a349d6abeb859051bcf34d34e0b4f31f9c9fb39ee1e0f7a1efe7b5198a39227a9d00b5a608f",
+        ),
+        (
+            Variant::PssZeroDeterministic,
+            "70726976616379207061737320746f6b656e",
+            "",
+            "0c6ebaa551f9b463639cc72f453da1bb2b5aec07df60b0552a7e656d796d51643c6629f478eb037414a7eb8fe698932acffc4ffb6e966b055fa9048beed8eb0dd4218c62ccc62e563c3c121b7db403460c90df6495f5783744fcd64ef860289d854798b64bac58af88331b7d1ce1da139809c91ae14769219288103810cc82ecb74b0a0748acc846fc808b1b24dbc7ae9475e02c5f30e7175a9a6114ba6f30625a04541b3461f03789f3cb0487ed2eb43404cb9d8648139715141d73d7f8d237c3b944ea3c11d09bd4876eefdab6ce612ccb2ba225e9e40f6014ef85458ebc63cf10caf40ca6db2b164a101a8c8813b1af71fe929738aeb45c36febd2b323134",
+            "f337b27cafbcfcaf55c7572ea423e63c0d325a25242f0dfb1f7d9bbba52f04005fd810280363d9cc6b1ce262f333fc574844a5de83dc97527558d034fe188b5558b4feb4273edc443d3d5595aab5df0fd02a3babd2e2cc705c6d7805855134d1b1b572b7264cb4367179e32d440709db880941d5b06281df29596b582bbf439aa8ca404c49c8be7764dfef0076f37549333375762b6d4e9acd90a67fdb6d6abd26a0d9c528a18cf95122ee3cf03e575959b5349860676647560f237141eeec486ca153d04f0a26da51e324d401b86da5d28378ca506bc782eabf9084177cc07999756dc2f8f2bfa5bc914bf643a86f9c87032a1b2aedfbb8f93d2ede8d86d31c",
+            "544396c94f14cd6dbe2620dad61dde7820789bed6368edf288f159e924734c9423f6c82b7602c4a2763bfabd47fcca3ced27f32bc12a946ce2d882b3b8b3a29a2e1953c3af87a5497bff683178404940dedb683a8648712186f42ff4a41b955fce8c5f47dbc2b3685048b45e3220f44e1d6e553e499932da6facc2a2a4919fba371f4c3930e55a6c7bf2c39d659cfb8e36086d2f365d2a7034d9b0f0bfd9c339259e747f6a78addf2e09ef837b3858e88961cef101d03c8178d1e40612adfd27851ac22c8daf494871b1bfd536f1ee288f274021a136b2d56d80ff0387431781b5e0ec407674b3018758c0cea0d516f30cf84a69380d81bcd32d146d62168802",
+        ),
+    ];
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::seed_from_u64(42)
+    }
+
+    #[test]
+    fn vectors() {
+        let key = test_key();
+        let n = key.public_key().n();
+        let inv = Mpi::from_bytes_be(&hex::decode(INV).unwrap());
+        let r = inv.mod_inv(n).unwrap();
+
+        for (variant, msg, salt, blinded_msg, blind_sig, sig) in VECTORS {
+            let msg = hex::decode(msg).unwrap();
+            let prefix_len = if variant.is_randomized() {
+                PREFIX_LEN
+            } else {
+                0
+            };
+            assert_eq!(&msg[prefix_len..], MSG);
+            let salt = hex::decode(salt).unwrap();
+            assert_eq!(salt.len(), variant.salt_len());
+
+            let (blinded, secret) = variant
+                .blind_with(&msg, key.public_key(), &salt, &r)
+                .unwrap();
+            assert_eq!(secret.inv, inv);
+            assert_eq!(hex::encode(&blinded), blinded_msg);
+            let signed = blind_sign(&key, &blinded, &mut rng()).unwrap();
+            assert_eq!(hex::encode(&signed), blind_sig);
+            let finalized = variant
+                .finalize(key.public_key(), &msg, &signed, &secret)
+                .unwrap();
+            assert_eq!(hex::encode(&finalized), sig);
+            assert!(variant.verify(key.public_key(), &msg, &finalized));
+        }
+    }
+
+    #[test]
+    fn protocol() {
+        let mut rng = rng();
+        let key = test_key();
+        let variants = [
+            Variant::PssRandomized,
+            Variant::PssZeroRandomized,
+            Variant::PssDeterministic,
+            Variant::PssZeroDeterministic,
+        ];
+
+        for variant in variants {
+            let msg = variant.prepare(MSG, &mut rng);
+            let prefix_len = if variant.is_randomized() {
+                PREFIX_LEN
+            } else {
+                0
+            };
+            assert_eq!(msg.len(), MSG.len() + prefix_len);
+
+            let (blinded, secret) = variant.blind(&msg, key.public_key(), &mut rng).unwrap();
+            let blind_sig = blind_sign(&key, &blinded, &mut rng).unwrap();
+            let sig = variant
+                .finalize(key.public_key(), &msg, &blind_sig, &secret)
+                .unwrap();
+
+            assert!(variant.verify(key.public_key(), &msg, &sig));
+            assert!(!variant.verify(key.public_key(), MSG.repeat(2), &sig));
+        }
+    }
+
+    #[test]
+    fn free_functions() {
+        let mut rng = rng();
+        let key = test_key();
+        let msg = Variant::PssRandomized.prepare(MSG, &mut rng);
+
+        let (blinded, secret) = blind(&msg, key.public_key(), &mut rng).unwrap();
+        let blind_sig = blind_sign(&key, &blinded, &mut rng).unwrap();
+        let sig = finalize(key.public_key(), &msg, &blind_sig, &secret).unwrap();
+
+        assert!(Variant::PssRandomized.verify(key.public_key(), &msg, &sig));
+        assert!(finalize(key.public_key(), MSG, &blind_sig, &secret).is_err());
+    }
+
+    #[test]
+    fn finalize_rejects() {
+        let mut rng = rng();
+        let key = test_key();
+        let variant = Variant::PssDeterministic;
+        let (blinded, secret) = variant.blind(MSG, key.public_key(), &mut rng).unwrap();
+        let mut blind_sig = blind_sign(&key, &blinded, &mut rng).unwrap();
+
+        assert!(variant
+            .finalize(key.public_key(), b"other", &blind_sig, &secret)
+            .is_err());
+        assert!(Variant::PssZeroDeterministic
+            .finalize(key.public_key(), MSG, &blind_sig, &secret)
+            .is_err());
+        assert!(variant
+            .finalize(key.public_key(), MSG, &blind_sig[1..], &secret)
+            .is_err());
+        blind_sig[0] ^= 1;
+        assert!(variant
+            .finalize(key.public_key(), MSG, &blind_sig, &secret)
+            .is_err());
+        assert!(blind_sign(&key, &blinded[1..], &mut rng).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use crate::traits::Hasher;
+
+/// MGF1 mask generation function (RFC 8017, B.2.1).
+pub fn mgf1<H: Hasher>(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut mask = Vec::with_capacity(len);
+    let mut counter = 0u32;
+    while mask.len() < len {
+        mask.extend(H::digest_parts(&[seed, &counter.to_be_bytes()]));
+        counter += 1;
+    }
+    mask.truncate(len);
+    mask
+}
+
+// Xors `data` with the MGF1 mask generated from `seed`.
+pub(super) fn mgf1_xor<H: Hasher>(data: &mut [u8], seed: &[u8]) {
+    let mask = mgf1::<H>(seed, data.len());
+    data.iter_mut().zip(mask).for_each(|(d, m)| *d ^= m);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sha1::Sha1, sha256::Sha256};
+
+    #[test]
+    fn mgf1_sha1() {
+        let mask = mgf1::<Sha1>(b"foo", 5);
+
+        assert_eq!(hex::encode(mask), "1ac9075cd4");
+    }
+
+    #[test]
+    fn mgf1_sha256() {
+        let mask = mgf1::<Sha256>(b"bar", 50);
+
+        assert_eq!(
+            hex::encode(mask),
+            "382576a7841021cc28fc4c0948753fb8312090cea942ea4c4e735d10dc724b155f9f6069f289d61daca0cb814502ef04eae1"
+        );
+    }
+}
@@ -0,0 +1,435 @@
+use crate::mpi::Mpi;
+use core::fmt;
+use rand_core::{CryptoRng, RngCore};
+
+pub mod blind;
+mod mgf;
+mod oaep;
+mod pss;
+
+pub use mgf::mgf1;
+pub use oaep::Oaep;
+pub use pss::{Pss, SaltLen};
+
+// Bases tried when recovering the factors of the modulus. Each base reveals
+// the factorization with probability at least 1/2.
+const RECOVERY_MAX_BASES: i64 = 100;
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "PublicComponents", into = "PublicComponents")
+)]
+pub struct RsaPublicKey {
+    n: Mpi,
+    e: Mpi,
+}
+
+impl RsaPublicKey {
+    pub fn new(n: Mpi, e: Mpi) -> Result<Self, String> {
+        if !n.is_odd() || n.is_negative() || n.bits_count() < 3 {
+            return Err("Invalid modulus".into());
+        }
+        if !e.is_odd() || e <= Mpi::from(1i64) || e >= n {
+            return Err("Invalid public exponent".into());
+        }
+        Ok(RsaPublicKey { n, e })
+    }
+
+    pub fn n(&self) -> &Mpi {
+        &self.n
+    }
+
+    pub fn e(&self) -> &Mpi {
+        &self.e
+    }
+
+    /// Modulus length in bytes.
+    pub fn size(&self) -> usize {
+        self.n.bytes_count()
+    }
+
+    /// Raw public operation `m^e mod n`, `m` must be in the range `[0, n)`.
+    pub fn public_op(&self, m: &Mpi) -> Result<Mpi, String> {
+        self.check_range(m)?;
+        Ok(m.mod_exp(&self.e, &self.n))
+    }
+
+    fn check_range(&self, x: &Mpi) -> Result<(), String> {
+        if x.is_negative() || x >= &self.n {
+            return Err("Input out of range".into());
+        }
+        Ok(())
+    }
+}
+
+/// RSA private key with precomputed CRT parameters.
+#[derive(Clone)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "PrivateComponents", into = "PrivateComponents")
+)]
+pub struct RsaPrivateKey {
+    public: RsaPublicKey,
+    d: Mpi,
+    p: Mpi,
+    q: Mpi,
+    // d mod (p - 1)
+    dp: Mpi,
+    // d mod (q - 1)
+    dq: Mpi,
+    // q^-1 mod p
+    qinv: Mpi,
+}
+
+impl RsaPrivateKey {
+    /// Builds a key from its components, checking their consistency.
+    ///
+    /// Both `p` and `q` must be prime, with `n = p * q`, and `d` must invert
+    /// `e` modulo `p - 1` and `q - 1`.
+    pub fn from_components(n: Mpi, e: Mpi, d: Mpi, p: Mpi, q: Mpi) -> Result<Self, String> {
+        let public = RsaPublicKey::new(n, e)?;
+        let one = Mpi::from(1i64);
+        if p <= one || q <= one || p == q || &(&p * &q) != public.n() {
+            return Err("Invalid prime factors".into());
+        }
+        if !p.is_prime() || !q.is_prime() {
+            return Err("Factors are not prime".into());
+        }
+        let p1 = &p - &one;
+        let q1 = &q - &one;
+        if d <= one || &d >= public.n() {
+            return Err("Invalid private exponent".into());
+        }
+        let de1 = &(&d * public.e()) - &one;
+        if !(&de1 % &p1).is_zero() || !(&de1 % &q1).is_zero() {
+            return Err("Inconsistent private exponent".into());
+        }
+        let qinv = q
+            .mod_inv(&p)
+            .ok_or_else(|| String::from("Invalid prime factors"))?;
+        Ok(RsaPrivateKey {
+            dp: &d % &p1,
+            dq: &d % &q1,
+            qinv,
+            public,
+            d,
+            p,
+            q,
+        })
+    }
+
+    /// Builds a key recovering the prime factors from `(n, e, d)`.
+    ///
+    /// Uses the probabilistic method of NIST SP 800-56B, appendix C, with a
+    /// deterministic sequence of bases. The larger prime is returned as `p`.
+    pub fn from_private_exponent(n: Mpi, e: Mpi, d: Mpi) -> Result<Self, String> {
+        let one = Mpi::from(1i64);
+        let n_minus_one = &n - &one;
+        // k = d * e - 1 is a multiple of lambda(n), thus even.
+        let k = &(&d * &e) - &one;
+        if k.is_negative() || k.is_zero() || k.is_odd() {
+            return Err("Inconsistent private exponent".into());
+        }
+        let t = k.trailing_zeros();
+        let r = k.shr(t);
+
+        for g in 2..RECOVERY_MAX_BASES {
+            let mut y = Mpi::from(g).mod_exp(&r, &n);
+            if y == one || y == n_minus_one {
+                continue;
+            }
+            for _ in 0..t {
+                let x = y.square().rem(&n);
+                if x == one {
+                    // y is a non trivial square root of one.
+                    let p = (&y - &one).gcd(&n);
+                    let q = &n / &p;
+                    let (p, q) = if p > q { (p, q) } else { (q, p) };
+                    return Self::from_components(n, e, d, p, q);
+                }
+                if x == n_minus_one {
+                    break;
+                }
+                y = x;
+            }
+        }
+        Err("Unable to factor the modulus".into())
+    }
+
+    pub fn public_key(&self) -> &RsaPublicKey {
+        &self.public
+    }
+
+    pub fn d(&self) -> &Mpi {
+        &self.d
+    }
+
+    pub fn p(&self) -> &Mpi {
+        &self.p
+    }
+
+    pub fn q(&self) -> &Mpi {
+        &self.q
+    }
+
+    pub fn dp(&self) -> &Mpi {
+        &self.dp
+    }
+
+    pub fn dq(&self) -> &Mpi {
+        &self.dq
+    }
+
+    pub fn qinv(&self) -> &Mpi {
+        &self.qinv
+    }
+
+    /// Modulus length in bytes.
+    pub fn size(&self) -> usize {
+        self.public.size()
+    }
+
+    /// Raw private operation `c^d mod n`, `c` must be in the range `[0, n)`.
+    ///
+    /// The exponentiation is split over `p` and `q` using the CRT, which is
+    /// about three times faster than a full exponentiation with `d`.
+    /// The input is blinded with a random `r^e` factor, so timings are not
+    /// correlated with `c`, and the result is verified before being returned
+    /// to avoid leaking the factors on faulty computations.
+    pub fn private_op<R: RngCore + CryptoRng>(&self, c: &Mpi, rng: &mut R) -> Result<Mpi, String> {
+        let n = self.public.n();
+        self.public.check_range(c)?;
+        let (r, r_inv) = loop {
+            let r = Mpi::random_below(n, rng);
+            if let Some(r_inv) = r.mod_inv(n) {
+                break (r, r_inv);
+            }
+        };
+        let blinded = (c * &r.mod_exp(self.public.e(), n)) % n;
+        let m = (&self.crt_exp(&blinded) * &r_inv) % n;
+        if &self.public.public_op(&m)? != c {
+            return Err("Private operation fault".into());
+        }
+        Ok(m)
+    }
+
+    // Garner's recombination of c^dp mod p and c^dq mod q.
+    fn crt_exp(&self, c: &Mpi) -> Mpi {
+        let m1 = (c % &self.p).mod_exp(&self.dp, &self.p);
+        let m2 = (c % &self.q).mod_exp(&self.dq, &self.q);
+        let h = (&self.qinv * &(&m1 - &m2)).rem_euclid(&self.p);
+        &m2 + &(&h * &self.q)
+    }
+}
+
+// Only the public part, the secret components are not printed.
+impl fmt::Debug for RsaPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RsaPrivateKey")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for RsaPrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.public == other.public && self.d == other.d
+    }
+}
+
+// Big-endian encoding of `x` left padded to `len` bytes (RFC 8017, 4.1).
+fn i2osp(x: &Mpi, len: usize) -> Result<Vec<u8>, String> {
+    let bytes = x.to_bytes_be();
+    if bytes.len() > len {
+        return Err("Integer too large".into());
+    }
+    let mut out = vec![0; len - bytes.len()];
+    out.extend_from_slice(&bytes);
+    Ok(out)
+}
+
+// Serialized forms, validated on deserialization.
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PublicComponents {
+    n: Mpi,
+    e: Mpi,
+}
+
+#[cfg(feature = "serde")]
+impl From<RsaPublicKey> for PublicComponents {
+    fn from(key: RsaPublicKey) -> Self {
+        PublicComponents { n: key.n, e: key.e }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PublicComponents> for RsaPublicKey {
+    type Error = String;
+
+    fn try_from(c: PublicComponents) -> Result<Self, Self::Error> {
+        RsaPublicKey::new(c.n, c.e)
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PrivateComponents {
+    n: Mpi,
+    e: Mpi,
+    d: Mpi,
+    p: Mpi,
+    q: Mpi,
+}
+
+#[cfg(feature = "serde")]
+impl From<RsaPrivateKey> for PrivateComponents {
+    fn from(key: RsaPrivateKey) -> Self {
+        PrivateComponents {
+            n: key.public.n,
+            e: key.public.e,
+            d: key.d,
+            p: key.p,
+            q: key.q,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PrivateComponents> for RsaPrivateKey {
+    type Error = String;
+
+    fn try_from(c: PrivateComponents) -> Result<Self, Self::Error> {
+        RsaPrivateKey::from_components(c.n, c.e, c.d, c.p, c.q)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    const P_HEX: &str = "ff89b19fd94c3e98d02e375de133a82e3575a55db256ede3a636069216ad10c6214f06e90a156d43dda9278f9dec29391cd1a0e9e0938401cd85e3f700c2ecd4f046cd22ea39f334d345d00c543a1fc83e4fcefebe9cae145b74943bd7aa4170a236a09faf759682844a3b45bc53d56961c6ca3484e717bc7fdfe7e949f076ab";
+    const Q_HEX: &str = "f5185d113e483bf251be0aa860d9d7e550b432769c81b3f59554c672b9d6f8949084a7ea8070b3af019c26353c68f0117759753c271c2ba61d1584a1fec833cc1fcd84d6f8bdfebd9ea17fb170fd6323d4d6069b7073dc2e48202fe1b8504382eff17925ad56a2e06d0977179a1529c437ed536ed62bebbd3cf8b56febf86ebb";
+
+    // (n, e, d, p, q) with d = e^-1 mod lcm(p - 1, q - 1)
+    fn components_from_primes(p: &str, q: &str, e: i64) -> (Mpi, Mpi, Mpi, Mpi, Mpi) {
+        let p = Mpi::from_hex(p).unwrap();
+        let q = Mpi::from_hex(q).unwrap();
+        let e = Mpi::from(e);
+        let one = Mpi::from(1i64);
+        let lambda = (&p - &one).lcm(&(&q - &one));
+        let d = e.mod_inv(&lambda).unwrap();
+        (&p * &q, e, d, p, q)
+    }
+
+    fn components() -> (Mpi, Mpi, Mpi, Mpi, Mpi) {
+        components_from_primes(P_HEX, Q_HEX, 65537)
+    }
+
+    pub(crate) fn key_from_primes(p: &str, q: &str, e: i64) -> RsaPrivateKey {
+        let (n, e, d, p, q) = components_from_primes(p, q, e);
+        RsaPrivateKey::from_components(n, e, d, p, q).unwrap()
+    }
+
+    pub(crate) fn test_key() -> RsaPrivateKey {
+        key_from_primes(P_HEX, Q_HEX, 65537)
+    }
+
+    #[test]
+    fn from_components() {
+        let key = test_key();
+        let (p, q) = (key.p(), key.q());
+        let one = Mpi::from(1i64);
+
+        assert_eq!(key.size(), 256);
+        assert_eq!(key.dp(), &(key.d() % &(p - &one)));
+        assert_eq!(key.dq(), &(key.d() % &(q - &one)));
+        assert_eq!((key.qinv() * q) % p, one);
+    }
+
+    #[test]
+    fn from_components_rejects_inconsistent() {
+        let (n, e, d, p, q) = components();
+        let two = Mpi::from(2i64);
+
+        let wrong_d = &d + &two;
+        assert!(RsaPrivateKey::from_components(
+            n.clone(),
+            e.clone(),
+            wrong_d,
+            p.clone(),
+            q.clone()
+        )
+        .is_err());
+        let wrong_n = &n + &two;
+        assert!(RsaPrivateKey::from_components(
+            wrong_n,
+            e.clone(),
+            d.clone(),
+            p.clone(),
+            q.clone()
+        )
+        .is_err());
+        assert!(
+            RsaPrivateKey::from_components(n.clone(), two, d.clone(), p.clone(), q.clone())
+                .is_err()
+        );
+        assert!(RsaPrivateKey::from_components(n, e, d, q, p).is_ok());
+    }
+
+    #[test]
+    fn from_private_exponent() {
+        let (n, e, d, p, q) = components();
+
+        let key = RsaPrivateKey::from_private_exponent(n, e, d).unwrap();
+
+        assert_eq!(key.p(), &p);
+        assert_eq!(key.q(), &q);
+        assert_eq!(key, test_key());
+    }
+
+    #[test]
+    fn private_op_inverts_public_op() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let key = test_key();
+        let public = key.public_key();
+
+        for _ in 0..5 {
+            let m = Mpi::random_below(public.n(), &mut rng);
+            let c = public.public_op(&m).unwrap();
+
+            let res = key.private_op(&c, &mut rng).unwrap();
+
+            assert_eq!(res, m);
+            assert_eq!(res, c.mod_exp(key.d(), public.n()));
+        }
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let key = test_key();
+        let n = key.public_key().n().clone();
+
+        assert!(key.public_key().public_op(&n).is_err());
+        assert!(key.private_op(&n, &mut rng).is_err());
+        assert!(key.private_op(&Mpi::from(-1i64), &mut rng).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_roundtrip() {
+        let key = test_key();
+
+        let json = serde_json::to_string(&key).unwrap();
+        let res: RsaPrivateKey = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(res, key);
+        assert!(serde_json::from_str::<RsaPublicKey>(r#"{"n":"10","e":"3"}"#).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+use super::{i2osp, mgf::mgf1_xor, RsaPrivateKey, RsaPublicKey};
+use crate::{mpi::Mpi, traits::Hasher};
+use core::marker::PhantomData;
+use rand_core::{CryptoRng, RngCore};
+use typenum::Unsigned;
+
+/// RSAES-OAEP encryption scheme (RFC 8017, 7.1) with `H` used both for the
+/// label digest and for MGF1.
+pub struct Oaep<H: Hasher> {
+    label: Vec<u8>,
+    _hasher: PhantomData<fn() -> H>,
+}
+
+impl<H: Hasher> Oaep<H> {
+    /// Scheme with an empty label.
+    pub fn new() -> Self {
+        Self::with_label([])
+    }
+
+    pub fn with_label(label: impl AsRef<[u8]>) -> Self {
+        Oaep {
+            label: label.as_ref().to_vec(),
+            _hasher: PhantomData,
+        }
+    }
+
+    /// Longest message which can be encrypted with `key`.
+    pub fn max_message_len(&self, key: &RsaPublicKey) -> usize {
+        key.size().saturating_sub(2 * H::DigestLen::USIZE + 2)
+    }
+
+    pub fn encrypt<R: RngCore + CryptoRng>(
+        &self,
+        key: &RsaPublicKey,
+        msg: impl AsRef<[u8]>,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, String> {
+        let mut seed = vec![0; H::DigestLen::USIZE];
+        rng.fill_bytes(&mut seed);
+        self.encrypt_with_seed(key, msg.as_ref(), &seed)
+    }
+
+    fn encrypt_with_seed(
+        &self,
+        key: &RsaPublicKey,
+        msg: &[u8],
+        seed: &[u8],
+    ) -> Result<Vec<u8>, String> {
+        let k = key.size();
+        let h_len = H::DigestLen::USIZE;
+        if k < 2 * h_len + 2 || msg.len() > self.max_message_len(key) {
+            return Err("Message too long".into());
+        }
+        // EM = 0x00 || maskedSeed || maskedDB
+        let mut em = vec![0; k];
+        let (masked_seed, db) = em[1..].split_at_mut(h_len);
+        // DB = lHash || PS || 0x01 || M
+        db[..h_len].copy_from_slice(&H::digest_parts(&[&self.label]));
+        let ps_end = db.len() - msg.len() - 1;
+        db[ps_end] = 0x01;
+        db[ps_end + 1..].copy_from_slice(msg);
+        masked_seed.copy_from_slice(seed);
+        mgf1_xor::<H>(db, masked_seed);
+        mgf1_xor::<H>(masked_seed, db);
+
+        let c = key.public_op(&Mpi::from_bytes_be(&em))?;
+        i2osp(&c, k)
+    }
+
+    /// Decrypts `ciphertext`, `rng` is used to blind the private operation.
+    ///
+    /// All the padding failures are reported with the same error and the
+    /// checks are carried out without branching on secret data.
+    pub fn decrypt<R: RngCore + CryptoRng>(
+        &self,
+        key: &RsaPrivateKey,
+        ciphertext: impl AsRef<[u8]>,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, String> {
+        let ciphertext = ciphertext.as_ref();
+        let k = key.size();
+        let h_len = H::DigestLen::USIZE;
+        if ciphertext.len() != k || k < 2 * h_len + 2 {
+            return Err("Decryption error".into());
+        }
+        let m = key
+            .private_op(&Mpi::from_bytes_be(ciphertext), rng)
+            .map_err(|_| String::from("Decryption error"))?;
+        let mut em = i2osp(&m, k)?;
+
+        let (y, rest) = em.split_at_mut(1);
+        let (masked_seed, masked_db) = rest.split_at_mut(h_len);
+        mgf1_xor::<H>(masked_seed, masked_db);
+        mgf1_xor::<H>(masked_db, masked_seed);
+        let db = masked_db;
+
+        let l_hash = H::digest_parts(&[&self.label]);
+        let mut bad = y[0];
+        bad |= db[..h_len]
+            .iter()
+            .zip(l_hash.iter())
+            .fold(0, |acc, (a, b)| acc | (a ^ b));
+
+        // Locate the 0x01 separator after the zero padding.
+        let mut looking = 1u8;
+        let mut index = 0;
+        for (i, &b) in db[h_len..].iter().enumerate() {
+            let is_one = (b == 1) as u8;
+            let is_zero = (b == 0) as u8;
+            index |= i & (looking & is_one).wrapping_neg() as usize;
+            bad |= looking & !is_one & !is_zero & 1;
+            looking &= !is_one;
+        }
+        bad |= looking;
+
+        if bad != 0 {
+            return Err("Decryption error".into());
+        }
+        Ok(db[h_len + index + 1..].to_vec())
+    }
+}
+
+impl<H: Hasher> Default for Oaep<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rsa::tests::{key_from_primes, test_key},
+        sha1::Sha1,
+        sha256::Sha256,
+        sha512::{Sha384, Sha512},
+    };
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // RSAES-OAEP example of the PKCS #1 v2.1 test vectors (oaep-int.txt),
+    // SHA-1 with an empty label and e = 17.
+    const EX_P: &str = "eecfae81b1b9b3c908810b10a1b5600199eb9f44aef4fda493b81a9e3d84f632124ef0236e5d1e3b7e28fae7aa040a2d5b252176459d1f397541ba2a58fb6599";
+    const EX_Q: &str = "c97fb1f027f453f6341233eaaad1d9353f6c42d08866b1d05a0f2035028b9d869840b41666b42e92ea0da3b43204b5cfce3352524d0416a5a441e700af461503";
+    const EX_N: &str = "bbf82f090682ce9c2338ac2b9da871f7368d07eed41043a440d6b6f07454f51fb8dfbaaf035c02ab61ea48ceeb6fcd4876ed520d60e1ec4619719d8a5b8b807fafb8e0a3dfc737723ee6b4b7d93a2584ee6a649d060953748834b2454598394ee0aab12d7b61a51f527a9a41f6c1687fe2537298ca2a8f5946f8e5fd091dbdcb";
+    const EX_MSG: &str = "d436e99569fd32a7c8a05bbc90d32c49";
+    const EX_SEED: &str = "aafd12f659cae63489b479e5076ddec2f06cb58f";
+    const EX_CT: &str = "1253e04dc0a5397bb44a7ab87e9bf2a039a33d1e996fc82a94ccd30074c95df763722017069e5268da5d1c0b4f872cf653c11df82314a67968dfeae28def04bb6d84b1c31d654a1970e5783bd6eb96a024c2ca2f4a90fe9f2ef5c9c140e5bb48da9536ad8700c84fc9130adea74e558d51a74ddf85d8b50de96838d6063e0955";
+
+    // Produced by OpenSSL with the `rsa::tests::test_key` public key, also
+    // decrypted by pyca/cryptography.
+    const MSG: &[u8] = b"OAEP interoperability test";
+    const CT_SHA1: &str = "796804ee4206213f73b367f6b4f6da84edaf45a55ca3cc43d28a90e8739b6d30a3d61ef6e570651927e1ce38242bef70d2417a34ef094666bcb05fea8b5a8d9215e984bd417fc5fe44a026368948858405f1949831e971629a2424b749e86e757849719f72f03ff4e46e0caf485352b907e1b27fa2fc91c64108ac561f75a8275e6ca41f8ff66507dd5363203bcae1e7319ab03b5b575abad598270418a70c3882d79e95af4ba233e0b5a8e77edc2074f337f01124edafbf5952db2c3ad4432610ea58a00a5917484c7d58df829390a923f65390328fcfb99e678f82b27cb77fdd9c05044a803e068bb0022b4ae283469dd306865e55038789b390d44d17ef4a";
+    const CT_SHA256: &str = "e39f2b6f9a0e234d05d73a5d697407a94a27ae00626da839b5f7944e00f0746ee720f8b84600fcda9278f57bbd145e80dd97cca4c32864089765ef467219b8a1ea8270e9bf2a05c21eb56a4a2d6e3b2fdd8c44553301024a1af5c44476f56472bd1d1639da7fe6e61775897aa116a6503f7e8d82219c84143dcaa0130a1e5fea7273c53bd9845220a239dbfef4a5ec455a0034910fa075b92446e6a6fed32f0523fae18d420f5f93e8c5d45bdd4d5f17d9b922978dc5e3a58f52b411e1cc269fcc961d657a7c34c083834e3e6793051cdf28c43788c6fe264ac23b5ce1815c9fba34a037fbe4a1d0e55c5a33b8180901f58f2b3c809cf6ea0492693ae5b83d42";
+    const CT_SHA256_LABEL: &str = "e36b637a6faa18d4556821891cf3318389692affaf584ef6e51163443888817574f534e7c10e888289f742fbcce018a2b67cb485b5e4b73fde0e44373e10a39fa2a37432173c6ff587734430019ca8714b31b0bff9feaeeb7f7375bb0a5d0966e2cb824ffe7d5d106848c56192876c9193ff1b51db2e9ca8ae96ce471e2c88bef91bb88c755be8cd90741e3758bd74e13c99eec78307dbf4f5a704b8a827f3f7dfad12387efd94e8dbc6c9fe694c1f50301cd325900c599aad90a6ecf14657723b8ac3d666f67222d73bdfb68b2898e7609e38f6c7acddf85e8962b6d2d9b8083a9be11fa77a5a9ca6b07f2c35df4f569b53db800fd40dc5cf5644addb94b8f2";
+    const CT_SHA512: &str = "9ef7235f33fdc3d46bfafc25f8ebf413b13ce5ea14c51bb42c2ac7f3d93de3f9da7237503b3a57b11a15ad270ab79ee7c447a5ce548c7b8efdf16cb64853b006988f006ce8da4f5ee409507d78ea4991f5fed3eb606c7b888ba29efc08669215e56e095a2a88d92ea2d15c8d05e37cd67420cb739a174786bd37ca2f5c6fcb5602c0ae9731dcf77c684c16d571edf7e4bea044cfa5a72fd0a1c8a530076d81e547acfa92e787725b3d5227906b1758f00a4beb797454727ff93128903b22fbf20b1824a284e54464568bc9cba74a32a836af661db5a8f63b88b8bdbe96b388b6a2e7fd43c700921ed40cfc98a4dd792f11501360f51e6d7833a9825f8b06e1a1";
+    const LABEL: &[u8] = b"job-queue";
+    // SHA-256 with a seed of 32 bytes equal to 7, decrypted by OpenSSL.
+    const CT_SHA256_SEED: &str = "7f95b33ac96ec73f0a400600f7f8ac857e1371e5fb2691b8862d796b3972e761529eeab30ae8443c1947c1888b7f5f28fc04ff1ef8d4100be5e34bb7f24232a536155f4428ad6247de49f6add8da94c4984036bd655da8b67501a8d43b4621797092e7c0838a2c06163aab0de91b6a6d6513e8397be221a1bb986ccf496f6fcba8da20cfb16f0d40194756e970a513e04dd7accba2ede9a330adae82dc338d4ecf6ee901dd27f776d04c9d532191b9e3ee5d32098c7ce10858e89f84d54b369d5e86a5ded169619b5503ef863d4ac77fee21fc6576f581c1878330e25ecf34e583011472de313a2c58ce436d608bcec8ee1698e6247a64ae160bad55e26d4a44";
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::seed_from_u64(42)
+    }
+
+    fn decrypt<H: Hasher>(oaep: Oaep<H>, ct: &str) -> Result<Vec<u8>, String> {
+        oaep.decrypt(&test_key(), hex::decode(ct).unwrap(), &mut rng())
+    }
+
+    #[test]
+    fn pkcs1_example() {
+        let key = key_from_primes(EX_P, EX_Q, 17);
+        let oaep = Oaep::<Sha1>::new();
+        let msg = hex::decode(EX_MSG).unwrap();
+        assert_eq!(format!("{:x}", key.public_key().n()), EX_N);
+
+        let ct = oaep
+            .encrypt_with_seed(key.public_key(), &msg, &hex::decode(EX_SEED).unwrap())
+            .unwrap();
+
+        assert_eq!(hex::encode(&ct), EX_CT);
+        assert_eq!(oaep.decrypt(&key, &ct, &mut rng()).unwrap(), msg);
+    }
+
+    #[test]
+    fn pkcs1_example_invalid() {
+        let mut rng = rng();
+        let key = key_from_primes(EX_P, EX_Q, 17);
+        let oaep = Oaep::<Sha1>::new();
+        let ct = hex::decode(EX_CT).unwrap();
+
+        // Modified ciphertext
+        for i in [0, 64, ct.len() - 1] {
+            let mut bad = ct.clone();
+            bad[i] ^= 0x80;
+            assert!(oaep.decrypt(&key, &bad, &mut rng).is_err());
+        }
+        // Wrong length, or out of range
+        let mut long = vec![0];
+        long.extend_from_slice(&ct);
+        assert!(oaep.decrypt(&key, &long, &mut rng).is_err());
+        assert!(oaep.decrypt(&key, &ct[1..], &mut rng).is_err());
+        let n = i2osp(key.public_key().n(), key.size()).unwrap();
+        assert!(oaep.decrypt(&key, &n, &mut rng).is_err());
+        // Wrong label or hash
+        let labeled = Oaep::<Sha1>::with_label(b"label");
+        assert!(labeled.decrypt(&key, &ct, &mut rng).is_err());
+        assert!(Oaep::<Sha256>::new().decrypt(&key, &ct, &mut rng).is_err());
+        // Valid padding with a non zero leading byte
+        let mut em = key.private_op(&Mpi::from_bytes_be(&ct), &mut rng).unwrap();
+        em = &em + &Mpi::from(1i64).shl(8 * (key.size() - 1));
+        let bad = i2osp(&key.public_key().public_op(&em).unwrap(), key.size()).unwrap();
+        assert!(oaep.decrypt(&key, &bad, &mut rng).is_err());
+        // Message too long
+        let msg = vec![0; oaep.max_message_len(key.public_key()) + 1];
+        assert!(oaep.encrypt(key.public_key(), &msg, &mut rng).is_err());
+    }
+
+    #[test]
+    fn decrypt_vectors() {
+        assert_eq!(decrypt(Oaep::<Sha1>::new(), CT_SHA1).unwrap(), MSG);
+        assert_eq!(decrypt(Oaep::<Sha256>::new(), CT_SHA256).unwrap(), MSG);
+        assert_eq!(
+            decrypt(Oaep::<Sha256>::with_label(LABEL), CT_SHA256_LABEL).unwrap(),
+            MSG
+        );
+        assert_eq!(decrypt(Oaep::<Sha512>::new(), CT_SHA512).unwrap(), MSG);
+    }
+
+    #[test]
+    fn decrypt_mismatch() {
+        assert!(decrypt(Oaep::<Sha256>::new(), CT_SHA1).is_err());
+        assert!(decrypt(Oaep::<Sha256>::new(), CT_SHA256_LABEL).is_err());
+        assert!(decrypt(Oaep::<Sha256>::with_label(LABEL), CT_SHA256).is_err());
+
+        let mut ct = hex::decode(CT_SHA256).unwrap();
+        ct[100] ^= 1;
+        assert!(Oaep::<Sha256>::new()
+            .decrypt(&test_key(), &ct, &mut rng())
+            .is_err());
+        assert!(Oaep::<Sha256>::new()
+            .decrypt(&test_key(), &ct[1..], &mut rng())
+            .is_err());
+    }
+
+    #[test]
+    fn roundtrip() {
+        let mut rng = rng();
+        let key = test_key();
+        let oaep = Oaep::<Sha384>::with_label(LABEL);
+        let max = oaep.max_message_len(key.public_key());
+
+        for len in [0, 1, max] {
+            let msg = vec![0xa5; len];
+            let ct = oaep.encrypt(key.public_key(), &msg, &mut rng).unwrap();
+
+            assert_eq!(ct.len(), key.size());
+            assert_eq!(oaep.decrypt(&key, &ct, &mut rng).unwrap(), msg);
+        }
+        assert!(oaep
+            .encrypt(key.public_key(), vec![0; max + 1], &mut rng)
+            .is_err());
+    }
+
+    #[test]
+    fn encrypt_with_seed() {
+        let key = test_key();
+        let oaep = Oaep::<Sha256>::new();
+
+        let ct = oaep
+            .encrypt_with_seed(key.public_key(), MSG, &[7; 32])
+            .unwrap();
+        let ct_rand = oaep.encrypt(key.public_key(), MSG, &mut rng()).unwrap();
+
+        assert_eq!(hex::encode(ct), CT_SHA256_SEED);
+        assert_ne!(hex::encode(ct_rand), CT_SHA256_SEED);
+    }
+}
@@ -0,0 +1,367 @@
+use super::{i2osp, mgf::mgf1_xor, RsaPrivateKey, RsaPublicKey};
+use crate::{mpi::Mpi, traits::Hasher};
+use core::marker::PhantomData;
+use rand_core::{CryptoRng, RngCore};
+use typenum::Unsigned;
+
+/// PSS salt length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SaltLen {
+    /// As long as the digest.
+    Digest,
+    /// The longest salt fitting the modulus.
+    Max,
+    Fixed(usize),
+    /// Signs as `Digest` and accepts any salt length on verification.
+    Auto,
+}
+
+/// RSASSA-PSS signature scheme (RFC 8017, 8.1) with `H` used both for the
+/// message digest and for MGF1.
+pub struct Pss<H: Hasher> {
+    salt_len: SaltLen,
+    _hasher: PhantomData<fn() -> H>,
+}
+
+const TRAILER: u8 = 0xbc;
+
+impl<H: Hasher> Pss<H> {
+    /// Scheme with `SaltLen::Auto`.
+    pub fn new() -> Self {
+        Self::with_salt_len(SaltLen::Auto)
+    }
+
+    pub fn with_salt_len(salt_len: SaltLen) -> Self {
+        Pss {
+            salt_len,
+            _hasher: PhantomData,
+        }
+    }
+
+    pub fn salt_len(&self) -> SaltLen {
+        self.salt_len
+    }
+
+    pub fn sign<R: RngCore + CryptoRng>(
+        &self,
+        key: &RsaPrivateKey,
+        msg: impl AsRef<[u8]>,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, String> {
+        self.sign_digest(key, H::digest_parts(&[msg.as_ref()]), rng)
+    }
+
+    /// Signs a message digest computed with `H`.
+    pub fn sign_digest<R: RngCore + CryptoRng>(
+        &self,
+        key: &RsaPrivateKey,
+        digest: impl AsRef<[u8]>,
+        rng: &mut R,
+    ) -> Result<Vec<u8>, String> {
+        let digest = digest.as_ref();
+        let h_len = H::DigestLen::USIZE;
+        if digest.len() != h_len {
+            return Err("Invalid digest length".into());
+        }
+        let em_bits = key.public_key().n().bits_count() - 1;
+        let em_len = em_bits.div_ceil(8);
+        if em_len < h_len + 2 {
+            return Err("Modulus too short".into());
+        }
+        let s_len = match self.salt_len {
+            SaltLen::Digest | SaltLen::Auto => h_len,
+            SaltLen::Max => em_len - h_len - 2,
+            SaltLen::Fixed(len) => len,
+        };
+        let mut salt = vec![0; s_len];
+        rng.fill_bytes(&mut salt);
+        let em = Self::encode(digest, &salt, em_bits)?;
+
+        let s = key.private_op(&Mpi::from_bytes_be(&em), rng)?;
+        i2osp(&s, key.size())
+    }
+
+    pub fn verify(
+        &self,
+        key: &RsaPublicKey,
+        msg: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+    ) -> bool {
+        self.verify_digest(key, H::digest_parts(&[msg.as_ref()]), signature)
+    }
+
+    /// Verifies a signature over a message digest computed with `H`.
+    pub fn verify_digest(
+        &self,
+        key: &RsaPublicKey,
+        digest: impl AsRef<[u8]>,
+        signature: impl AsRef<[u8]>,
+    ) -> bool {
+        let (digest, signature) = (digest.as_ref(), signature.as_ref());
+        let h_len = H::DigestLen::USIZE;
+        if digest.len() != h_len || signature.len() != key.size() {
+            return false;
+        }
+        let m = match key.public_op(&Mpi::from_bytes_be(signature)) {
+            Ok(m) => m,
+            Err(_) => return false,
+        };
+        let em_bits = key.n().bits_count() - 1;
+        let em_len = em_bits.div_ceil(8);
+        if em_len < h_len + 2 || m.bits_count() > em_bits {
+            return false;
+        }
+        let mut em = match i2osp(&m, em_len) {
+            Ok(em) => em,
+            Err(_) => return false,
+        };
+        if em[em_len - 1] != TRAILER {
+            return false;
+        }
+        let (db, rest) = em.split_at_mut(em_len - h_len - 1);
+        let h = &rest[..h_len];
+        mgf1_xor::<H>(db, h);
+        db[0] &= 0xff >> (8 * em_len - em_bits);
+
+        // DB = PS || 0x01 || salt
+        let sep = match db.iter().position(|&b| b != 0) {
+            Some(i) if db[i] == 0x01 => i,
+            _ => return false,
+        };
+        let s_len = db.len() - sep - 1;
+        let expected = match self.salt_len {
+            SaltLen::Digest => Some(h_len),
+            SaltLen::Max => Some(em_len - h_len - 2),
+            SaltLen::Fixed(len) => Some(len),
+            SaltLen::Auto => None,
+        };
+        if expected.is_some_and(|len| len != s_len) {
+            return false;
+        }
+        Self::m_prime_digest(digest, &db[sep + 1..]) == h
+    }
+
+    /// EMSA-PSS encoding of a message digest in `em_bits` bits.
+    pub(super) fn encode(digest: &[u8], salt: &[u8], em_bits: usize) -> Result<Vec<u8>, String> {
+        let h_len = H::DigestLen::USIZE;
+        let em_len = em_bits.div_ceil(8);
+        if em_len < h_len + salt.len() + 2 {
+            return Err("Salt too long".into());
+        }
+        // EM = maskedDB || H || 0xbc, with DB = PS || 0x01 || salt
+        let mut em = vec![0; em_len];
+        let (db, rest) = em.split_at_mut(em_len - h_len - 1);
+        let (h, trailer) = rest.split_at_mut(h_len);
+        h.copy_from_slice(&Self::m_prime_digest(digest, salt));
+        trailer[0] = TRAILER;
+        let ps_end = db.len() - salt.len() - 1;
+        db[ps_end] = 0x01;
+        db[ps_end + 1..].copy_from_slice(salt);
+        mgf1_xor::<H>(db, h);
+        db[0] &= 0xff >> (8 * em_len - em_bits);
+        Ok(em)
+    }
+
+    // H(0x00 * 8 || mHash || salt)
+    fn m_prime_digest(digest: &[u8], salt: &[u8]) -> Vec<u8> {
+        H::digest_parts(&[&[0; 8], digest, salt])
+    }
+}
+
+impl<H: Hasher> Default for Pss<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rsa::tests::{key_from_primes, test_key},
+        sha1::Sha1,
+        sha256::Sha256,
+        sha512::{Sha384, Sha512},
+    };
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // RSASSA-PSS example of the PKCS #1 v2.1 test vectors (pss-int.txt),
+    // SHA-1 with a 20 bytes salt.
+    const EX_P: &str = "d17f655bf27c8b16d35462c905cc04a26f37e2a67fa9c0ce0dced472394a0df743fe7f929e378efdb368eddff453cf007af6d948e0ade757371f8a711e278f6b";
+    const EX_Q: &str = "c6d92b6fee7414d1358ce1546fb62987530b90bd15e0f14963a5e2635adb69347ec0c01b2ab1763fd8ac1a592fb22757463a982425bb97a3a437c5bf86d03f2f";
+    const EX_N: &str = "a2ba40ee07e3b2bd2f02ce227f36a195024486e49c19cb41bbbdfbba98b22b0e577c2eeaffa20d883a76e65e394c69d4b3c05a1e8fadda27edb2a42bc000fe888b9b32c22d15add0cd76b3e7936e19955b220dd17d4ea904b1ec102b2e4de7751222aa99151024c7cb41cc5ea21d00eeb41f7c800834d2c6e06bce3bce7ea9a5";
+    const EX_MSG: &str = "859eef2fd78aca00308bdc471193bf55bf9d78db8f8a672b484634f3c9c26e6478ae10260fe0dd8c082e53a5293af2173cd50c6d5d354febf78b26021c25c02712e78cd4694c9f469777e451e7f8e9e04cd3739c6bbfedae487fb55644e9ca74ff77a53cb729802f6ed4a5ffa8ba159890fc";
+    const EX_SALT: &str = "e3b5d5d002c1bce50c2b65ef88a188d83bce7e61";
+    const EX_SIG: &str = "8daa627d3de7595d63056c7ec659e54406f10610128baae821c8b2a0f3936d54dc3bdce46689f6b7951bb18e840542769718d5715d210d85efbb596192032c42be4c29972c856275eb6d5a45f05f51876fc6743deddd28caec9bb30ea99e02c3488269604fe497f74ccd7c7fca1671897123cbd30def5d54a2b5536ad90a747e";
+
+    // Produced by OpenSSL with the `rsa::tests::test_key` private key, also
+    // verified by pyca/cryptography.
+    const MSG: &[u8] = b"PSS interoperability test";
+    const SIG_SHA256: &str = "671225c01596abcab03746f908cb742b0642c126d81ad7bef41a0890c222a4c8cb46059f87012daabfdc762152a8cc930c50645f20421cb046777e36960c016542ad62f1a24069b76f592d1787c633d47eab89b50b01509b1f48547836a571d9101c2d5d37bc5c6514441de6c7912eb797d472f8c4a02df28141be03c33b56197b4fe0747bad367441f705fd4aacb31dd3ecd3d2ca69df7fd1204cf0af5cb32394dc4e0304e23455fc98b0ca5de3c5c9ca9967060594532fe760f177a7b125b495f74ac20dde1768dda9abcab5bce7c5bdb350ff32d15e69a772d0688253a5fb8d9feea4ebec9b91694ebec05b53387532f8ee89f4bd0960d77febead818fad5";
+    const SIG_SHA384_MAX: &str = "b0b7da73ff96d552be4b63c838f1adc6d1038e2712492fe39e56864a2c04b08f1d3d156412e555784fe4617d8f904e6b97dd23fd1a4251c0ea361bb2f611942da6efbe4532c00d4e45f8ebc5e2a1a51d8349071bba8aeb5a2100cd223aa6bf34330d706046c1bb4fada6d8c7e718f130910f3b0b6dfdd5264a588525fc557739cc0ec94b20de7e3691bd4a70e38c01ff113f0d6085addf2fcabf1e4176a5a050008262cf4efee36b4ff7ff615260c6f71156721bb5a99ed073730de3994fd8addbf5a8f9ba7f7657848d51e6da58cc0e3f9ed4869691f723760ea0a2b04cfc9da2286811b14fdb1ec98e0662890d2f999071189c741e4d187b2a5c78acaaf2c8";
+    const SIG_SHA1: &str = "b217a70f8442f256785f6dc6f50c2a2b1bb6b6a983367686e61ad1ea488071754af4a2c2482c92b55b37451db39b7a38ba7cc77e1c5c0592c8cb776bf942874d290b1317aae20c469e0e7cd206031d85be54cc9301d733c299acd94109807a05d0afb6ab87795e72454ad1e3bfb0570526a3bfa76998ab4a8136c4da739e2ae4ac2cdace9d714b4abf672cb0cb80b6c0aae2bbd18d87aad8da169e5af0a7c420ec472b94c5a9f426ee473b64cb2326c48e8cb2ede404c7b71c061c1e976112855924e00ff16d8ba2202c7b9842e35a83b35abcafe47528449b75cac3e36be1c1346e1ba5d921c019ab818aa2841b668a13fbbf376e46a83d4a43cbcaa6c682da";
+    // Deterministic, the salt is empty.
+    const SIG_SHA512_EMPTY_SALT: &str = "01106f0173da3fb103decc7eb467d3293a0ba05f21ed8e57d7016f5ccfc07967ff9eb4c4ef6d6a9575576776d04d920f1bbca9bd9fbd837928e2d9844fd499d038f1067f5f6e3fc05f29d23932a4d1b6a1f41a51f0a7c53a120e4f5169f69a8889aa6cadfb819b2305d55a8e0faed92d3e964a232a41eacb804fcf4a3430259f35e8ff2a9d0d541e18bea0e049ef49f7b0b545920063819811f86e6e85f45b48c54a3cda62991b0d77713d098cb443be25743e4358d73bb3addae4656bee946730834736cc4cd8d073534f321236d6366be6a2f16041b55dc7fb68180e273c93e16b4c6c065e322e2d8073741edee82c1a68d2b2a1e4918a126d729a89b50e29";
+
+    fn rng() -> ChaCha20Rng {
+        ChaCha20Rng::seed_from_u64(42)
+    }
+
+    fn verify<H: Hasher>(pss: Pss<H>, sig: &str) -> bool {
+        pss.verify(test_key().public_key(), MSG, hex::decode(sig).unwrap())
+    }
+
+    fn example_key() -> RsaPrivateKey {
+        key_from_primes(EX_P, EX_Q, 65537)
+    }
+
+    // Raw private operation on an encoded message.
+    fn sign_raw(key: &RsaPrivateKey, em: &[u8]) -> Vec<u8> {
+        let s = key.private_op(&Mpi::from_bytes_be(em), &mut rng()).unwrap();
+        i2osp(&s, key.size()).unwrap()
+    }
+
+    #[test]
+    fn pkcs1_example() {
+        let key = example_key();
+        let msg = hex::decode(EX_MSG).unwrap();
+        let digest = Sha1::digest_parts(&[&msg]);
+        let em_bits = key.public_key().n().bits_count() - 1;
+        assert_eq!(format!("{:x}", key.public_key().n()), EX_N);
+
+        let em = Pss::<Sha1>::encode(&digest, &hex::decode(EX_SALT).unwrap(), em_bits).unwrap();
+        let sig = sign_raw(&key, &em);
+
+        assert_eq!(hex::encode(&sig), EX_SIG);
+        for salt_len in [SaltLen::Digest, SaltLen::Fixed(20), SaltLen::Auto] {
+            let pss = Pss::<Sha1>::with_salt_len(salt_len);
+            assert!(pss.verify(key.public_key(), &msg, &sig));
+        }
+    }
+
+    #[test]
+    fn pkcs1_example_invalid() {
+        let key = example_key();
+        let public = key.public_key();
+        let pss = Pss::<Sha1>::new();
+        let msg = hex::decode(EX_MSG).unwrap();
+        let sig = hex::decode(EX_SIG).unwrap();
+        let em = public.public_op(&Mpi::from_bytes_be(&sig)).unwrap();
+        let em = i2osp(&em, key.size()).unwrap();
+
+        // Wrong salt length, message or hash
+        for salt_len in [SaltLen::Fixed(19), SaltLen::Fixed(21), SaltLen::Max] {
+            let pss = Pss::<Sha1>::with_salt_len(salt_len);
+            assert!(!pss.verify(public, &msg, &sig));
+        }
+        assert!(!pss.verify(public, &msg[1..], &sig));
+        assert!(!Pss::<Sha256>::new().verify(public, &msg, &sig));
+        // Modified signature
+        for i in [0, 64, sig.len() - 1] {
+            let mut bad = sig.clone();
+            bad[i] ^= 0x01;
+            assert!(!pss.verify(public, &msg, &bad));
+        }
+        // Wrong length, or out of range
+        let mut long = vec![0];
+        long.extend_from_slice(&sig);
+        assert!(!pss.verify(public, &msg, &long));
+        assert!(!pss.verify(public, &msg, &sig[1..]));
+        let n = i2osp(public.n(), key.size()).unwrap();
+        assert!(!pss.verify(public, &msg, &n));
+        // Wrong trailer, or bits set above the encoded message length
+        let mut bad = em.clone();
+        bad[em.len() - 1] = 0xbd;
+        assert!(!pss.verify(public, &msg, sign_raw(&key, &bad)));
+        // Top bit set, still below n
+        let mut bad = em;
+        bad[0] = (bad[0] & 0x1f) | 0x80;
+        assert!(!pss.verify(public, &msg, sign_raw(&key, &bad)));
+    }
+
+    #[test]
+    fn verify_vectors() {
+        assert!(verify(Pss::<Sha256>::new(), SIG_SHA256));
+        assert!(verify(
+            Pss::<Sha256>::with_salt_len(SaltLen::Digest),
+            SIG_SHA256
+        ));
+        assert!(verify(Pss::<Sha384>::new(), SIG_SHA384_MAX));
+        assert!(verify(
+            Pss::<Sha384>::with_salt_len(SaltLen::Max),
+            SIG_SHA384_MAX
+        ));
+        assert!(verify(
+            Pss::<Sha1>::with_salt_len(SaltLen::Fixed(20)),
+            SIG_SHA1
+        ));
+        assert!(verify(Pss::<Sha512>::new(), SIG_SHA512_EMPTY_SALT));
+    }
+
+    #[test]
+    fn verify_rejects() {
+        let key = test_key();
+
+        assert!(!verify(
+            Pss::<Sha256>::with_salt_len(SaltLen::Max),
+            SIG_SHA256
+        ));
+        assert!(!verify(
+            Pss::<Sha384>::with_salt_len(SaltLen::Digest),
+            SIG_SHA384_MAX
+        ));
+        assert!(!verify(Pss::<Sha512>::new(), SIG_SHA256));
+
+        let mut sig = hex::decode(SIG_SHA256).unwrap();
+        assert!(!Pss::<Sha256>::new().verify(key.public_key(), b"other", &sig));
+        sig[10] ^= 1;
+        assert!(!Pss::<Sha256>::new().verify(key.public_key(), MSG, &sig));
+        assert!(!Pss::<Sha256>::new().verify(key.public_key(), MSG, &sig[1..]));
+    }
+
+    #[test]
+    fn sign_empty_salt() {
+        let pss = Pss::<Sha512>::with_salt_len(SaltLen::Fixed(0));
+
+        let sig = pss.sign(&test_key(), MSG, &mut rng()).unwrap();
+
+        assert_eq!(hex::encode(sig), SIG_SHA512_EMPTY_SALT);
+    }
+
+    #[test]
+    fn sign_verify() {
+        let mut rng = rng();
+        let key = test_key();
+        let salt_lens = [
+            SaltLen::Auto,
+            SaltLen::Digest,
+            SaltLen::Max,
+            SaltLen::Fixed(0),
+            SaltLen::Fixed(7),
+        ];
+
+        for salt_len in salt_lens {
+            let pss = Pss::<Sha256>::with_salt_len(salt_len);
+            let sig = pss.sign(&key, MSG, &mut rng).unwrap();
+
+            assert!(pss.verify(key.public_key(), MSG, &sig));
+            assert!(Pss::<Sha256>::new().verify(key.public_key(), MSG, &sig));
+        }
+        let pss = Pss::<Sha256>::with_salt_len(SaltLen::Fixed(256));
+        assert!(pss.sign(&key, MSG, &mut rng).is_err());
+    }
+
+    #[test]
+    fn sign_digest() {
+        let mut rng = rng();
+        let key = test_key();
+        let pss = Pss::<Sha384>::new();
+        let digest = Sha384::digest_parts(&[MSG]);
+
+        let sig = pss.sign_digest(&key, &digest, &mut rng).unwrap();
+
+        assert!(pss.verify(key.public_key(), MSG, &sig));
+        assert!(pss.verify_digest(key.public_key(), &digest, &sig));
+        assert!(pss.sign_digest(&key, &digest[1..], &mut rng).is_err());
+    }
+}
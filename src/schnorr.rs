@@ -0,0 +1,588 @@
+//! BIP-340 Schnorr signatures over secp256k1.
+//!
+//! Public keys are x-only: the point with even `y` is implied, so keys are 32
+//! bytes and signatures are 64 bytes.
+
+use crate::{
+    batch,
+    ec::{Curve, Group, Point},
+    mpi::Mpi,
+    sha256::Sha256,
+};
+use rand_core::{CryptoRng, RngCore};
+
+/// SHA-256 of `SHA256(tag) || SHA256(tag) || parts`.
+pub fn tagged_hash(tag: &str, parts: &[&[u8]]) -> [u8; 32] {
+    let mut tag_hash = Sha256::new();
+    tag_hash.update(tag);
+    let tag_hash = tag_hash.digest();
+    let mut ctx = Sha256::new();
+    ctx.update(tag_hash);
+    ctx.update(tag_hash);
+    for part in parts {
+        ctx.update(part);
+    }
+    ctx.digest()
+}
+
+fn group() -> Group {
+    Group::new(Curve::Secp256k1)
+}
+
+// 32 bytes big-endian encoding of a field element or scalar.
+fn to_bytes32(x: &Mpi) -> [u8; 32] {
+    let bytes = x.to_bytes_be();
+    let mut out = [0; 32];
+    out[32 - bytes.len()..].copy_from_slice(&bytes);
+    out
+}
+
+// Point with the given `x` and an even `y`, if any.
+fn lift_x(grp: &Group, x: &Mpi) -> Option<Point> {
+    let p = grp.p();
+    if x >= p {
+        return None;
+    }
+    let c = (&(&(x * x) * x) + grp.b()) % p;
+    let y = c.mod_sqrt(p)?;
+    let y = if y.is_odd() { p - &y } else { y };
+    Some(Point::new(x.clone(), y))
+}
+
+// e = int(hash_challenge(R.x || P.x || m)) mod n
+fn challenge(grp: &Group, r: &[u8], p: &[u8; 32], msg: &[u8]) -> Mpi {
+    let e = tagged_hash("BIP0340/challenge", &[r, p, msg]);
+    Mpi::from_bytes_be(&e) % grp.order()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Signature(#[cfg_attr(feature = "serde", serde(with = "crate::serde::bytes"))] [u8; 64]);
+
+impl Signature {
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let bytes = bytes.try_into().map_err(|_| "Invalid signature length")?;
+        Ok(Signature(bytes))
+    }
+
+    pub fn to_bytes(&self) -> [u8; 64] {
+        self.0
+    }
+}
+
+/// X-only public key.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VerifyingKey {
+    point: Point,
+}
+
+impl VerifyingKey {
+    /// Decodes an x-only key, lifting it to the point with even `y`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != 32 {
+            return Err("Invalid public key length".into());
+        }
+        let point = lift_x(&group(), &Mpi::from_bytes_be(bytes)).ok_or("Invalid public key")?;
+        Ok(VerifyingKey { point })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        to_bytes32(self.point.x())
+    }
+
+    pub fn point(&self) -> &Point {
+        &self.point
+    }
+
+    pub fn verify(&self, msg: impl AsRef<[u8]>, signature: &Signature) -> bool {
+        let grp = group();
+        let (r, s) = signature.0.split_at(32);
+        let r_int = Mpi::from_bytes_be(r);
+        let s = Mpi::from_bytes_be(s);
+        if &r_int >= grp.p() || &s >= grp.order() {
+            return false;
+        }
+        let e = challenge(&grp, r, &self.to_bytes(), msg.as_ref());
+        // R = s * G - e * P
+        let neg_e = (grp.order() - &e) % grp.order();
+        let big_r = grp.add(&grp.mul_generator(&s), &grp.mul(&neg_e, &self.point));
+        !big_r.is_identity() && !big_r.y().is_odd() && big_r.x() == &r_int
+    }
+}
+
+/// Item of a batch verification.
+pub type BatchItem<'a> = (&'a VerifyingKey, &'a [u8], &'a Signature);
+
+/// Checks all the signatures of the batch at once (BIP-340, batch
+/// verification).
+///
+/// The verification equations, multiplied by random coefficients, are summed
+/// into a single multi-scalar multiplication, which costs a fraction of the
+/// individual verifications. The coefficients are derived from a hash of the
+/// whole batch.
+pub fn verify_batch(items: &[BatchItem]) -> bool {
+    let grp = group();
+    let n = grp.order();
+    let seed = batch_seed(items);
+    let mut s_sum = Mpi::new();
+    let mut terms = Vec::with_capacity(2 * items.len());
+    for (i, (key, msg, sig)) in items.iter().enumerate() {
+        let (r, s) = sig.0.split_at(32);
+        let s = Mpi::from_bytes_be(s);
+        if &s >= n {
+            return false;
+        }
+        let Some(big_r) = lift_x(&grp, &Mpi::from_bytes_be(r)) else {
+            return false;
+        };
+        let e = challenge(&grp, r, &key.to_bytes(), msg);
+        let a = if i == 0 {
+            Mpi::from(1i64)
+        } else {
+            batch_coefficient(&seed, i)
+        };
+        s_sum = (&s_sum + &(&a * &s)) % n;
+        terms.push(((&a * &e) % n, key.point.clone()));
+        terms.push((a, big_r));
+    }
+    // sum(a_i * s_i) * G == sum(a_i * R_i) + sum(a_i * e_i * P_i)
+    let neg_s = (n - &s_sum) % n;
+    grp.generator_table().mul_add(&neg_s, &terms).is_identity()
+}
+
+/// Validity of each signature of the batch. The batch is checked as a whole
+/// first, the signatures are verified one by one only if that fails.
+pub fn verify_batch_each(items: &[BatchItem]) -> Vec<bool> {
+    if verify_batch(items) {
+        return vec![true; items.len()];
+    }
+    // The keys are sent to the verifying threads as coordinates bytes.
+    let items: Vec<_> = items
+        .iter()
+        .map(|(key, msg, sig)| (key.to_bytes(), to_bytes32(key.point.y()), *msg, **sig))
+        .collect();
+    batch::verify_each(
+        &items,
+        || (),
+        |_, (x, y, msg, sig)| {
+            let point = Point::new(Mpi::from_bytes_be(x), Mpi::from_bytes_be(y));
+            VerifyingKey { point }.verify(msg, sig)
+        },
+    )
+}
+
+fn batch_seed(items: &[BatchItem]) -> [u8; 32] {
+    let mut ctx = Sha256::new();
+    for (key, msg, sig) in items {
+        ctx.update(key.to_bytes());
+        ctx.update((msg.len() as u64).to_be_bytes());
+        ctx.update(msg);
+        ctx.update(sig.0);
+    }
+    ctx.digest()
+}
+
+// 128 bits coefficient of the i-th equation.
+fn batch_coefficient(seed: &[u8; 32], i: usize) -> Mpi {
+    let hash = tagged_hash("BIP0340/batch", &[seed, &(i as u64).to_be_bytes()]);
+    Mpi::from_bytes_be(&hash[..16])
+}
+
+#[derive(Clone)]
+pub struct SigningKey {
+    d: Mpi,
+    public: VerifyingKey,
+}
+
+impl SigningKey {
+    /// Key with secret scalar `d`, which must be in the range `[1, n)`.
+    pub fn new(d: Mpi) -> Result<Self, String> {
+        let grp = group();
+        if d.is_zero() || d.is_negative() || &d >= grp.order() {
+            return Err("Invalid private key".into());
+        }
+        let point = grp.mul_generator(&d);
+        // Keep the secret matching the even `y` public point.
+        let (d, point) = if point.y().is_odd() {
+            let y = grp.p() - point.y();
+            (grp.order() - &d, Point::new(point.x().clone(), y))
+        } else {
+            (d, point)
+        };
+        Ok(SigningKey {
+            d,
+            public: VerifyingKey { point },
+        })
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() != 32 {
+            return Err("Invalid private key length".into());
+        }
+        Self::new(Mpi::from_bytes_be(bytes))
+    }
+
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let n = group().order().clone();
+        loop {
+            let d = Mpi::random_below(&n, rng);
+            if let Ok(key) = Self::new(d) {
+                return key;
+            }
+        }
+    }
+
+    pub fn verifying_key(&self) -> &VerifyingKey {
+        &self.public
+    }
+
+    /// Signs with 32 bytes of auxiliary randomness mixed into the nonce.
+    pub fn sign_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        msg: impl AsRef<[u8]>,
+        rng: &mut R,
+    ) -> Result<Signature, String> {
+        let mut aux = [0; 32];
+        rng.fill_bytes(&mut aux);
+        self.sign(msg, &aux)
+    }
+
+    /// Signs with the given auxiliary randomness. The nonce is derived from
+    /// the key and the message too, so a constant `aux` only loses the
+    /// protection against side channels.
+    pub fn sign(&self, msg: impl AsRef<[u8]>, aux: &[u8; 32]) -> Result<Signature, String> {
+        let msg = msg.as_ref();
+        let grp = group();
+        let n = grp.order();
+        let p_bytes = self.public.to_bytes();
+
+        let mut t = tagged_hash("BIP0340/aux", &[aux]);
+        for (t, d) in t.iter_mut().zip(to_bytes32(&self.d)) {
+            *t ^= d;
+        }
+        let rand = tagged_hash("BIP0340/nonce", &[&t, &p_bytes, msg]);
+        let k = Mpi::from_bytes_be(&rand) % n;
+        if k.is_zero() {
+            return Err("Signature generation failure".into());
+        }
+        let big_r = grp.mul_generator(&k);
+        let k = if big_r.y().is_odd() { n - &k } else { k };
+        let r = to_bytes32(big_r.x());
+        let e = challenge(&grp, &r, &p_bytes, msg);
+        let s = (&k + &(&e * &self.d)) % n;
+
+        let mut sig = [0; 64];
+        sig[..32].copy_from_slice(&r);
+        sig[32..].copy_from_slice(&to_bytes32(&s));
+        let sig = Signature(sig);
+        // Guard against faulty computations leaking the key.
+        if !self.public.verify(msg, &sig) {
+            return Err("Signature generation failure".into());
+        }
+        Ok(sig)
+    }
+}
+
+// Only the public part, the secret scalar is not printed.
+impl core::fmt::Debug for SigningKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SigningKey")
+            .field("public", &self.public)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The 32 bytes x-only encoding, validated on deserialization.
+#[cfg(feature = "serde")]
+impl serde::Serialize for VerifyingKey {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        crate::serde::bytes::serialize(&self.to_bytes(), s)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for VerifyingKey {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let bytes: Vec<u8> = crate::serde::bytes::deserialize(d)?;
+        Self::from_bytes(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    // BIP-340 test vectors 0 to 3 and 15 to 18: (secret key, public key, aux,
+    // message, signature).
+    const SIGN_VECTORS: [(&str, &str, &str, &str, &str); 8] = [
+        (
+            "0000000000000000000000000000000000000000000000000000000000000003",
+            "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+        ),
+        (
+            "b7e151628aed2a6abf7158809cf4f3c762e7160f38b4da56a784d9045190cfef",
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de33418906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+        ),
+        (
+            "c90fdaa22168c234c4c6628b80dc1cd129024e088a67cc74020bbea63b14e5c9",
+            "dd308afec5777e13121fa72b9cc1b7cc0139715309b086c960e18fd969774eb8",
+            "c87aa53824b4d7ae2eb035a2b5bbbccc080e76cdc6d1692c4b0b62d798e6d906",
+            "7e2d58d8b3bcdf1abadec7829054f90dda9805aab56c77333024b9d0a508b75c",
+            "5831aaeed7b44bb74e5eab94ba9d4294c49bcf2a60728d8b4c200f50dd313c1bab745879a5ad954a72c45a91c3a51d3c7adea98d82f8481e0e1e03674a6f3fb7",
+        ),
+        (
+            "0b432b2677937381aef05bb02a66ecd012773062cf3fa2549e44f58ed2401710",
+            "25d1dff95105f5253c4022f628a996ad3a0d95fbf21d468a1b33f8c160d8f517",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "7eb0509757e246f19449885651611cb965ecc1a187dd51b64fda1edc9637d5ec97582b9cb13db3933705b32ba982af5af25fd78881ebb32771fc5922efc66ea3",
+        ),
+        // Message of size 0
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "",
+            "71535db165ecd9fbbc046e5ffaea61186bb6ad436732fccc25291a55895464cf6069ce26bf03466228f19a3a62db8a649f2d560fac652827d1af0574e427ab63",
+        ),
+        // Message of size 1
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "11",
+            "08a20a0afef64124649232e0693c583ab1b9934ae63b4c3511f3ae1134c6a303ea3173bfea6683bd101fa5aa5dbc1996fe7cacfc5a577d33ec14564cec2bacbf",
+        ),
+        // Message of size 17
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "0102030405060708090a0b0c0d0e0f1011",
+            "5130f39a4059b43bc7cac09a19ece52b5d8699d1a71e3c52da9afdb6b50ac370c4a482b77bf960f8681540e25b6771ece1e5a37fd80e5a51897c5566a97ea5a5",
+        ),
+        // Message of size 100
+        (
+            "0340034003400340034003400340034003400340034003400340034003400340",
+            "778caa53b4393ac467774d09497a87224bf9fab6f6e68b23086497324d6fd117",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "99999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999999",
+            "403b12b0d8555a344175ea7ec746566303321e5dbfa8be6f091635163eca79a8585ed3e3170807e7c03b720fc54c7b23897fcba0e9d0b4a06894cfd249f22367",
+        ),
+    ];
+
+    // BIP-340 test vectors 4 to 14: (public key, message, signature, result).
+    const VERIFY_VECTORS: [(&str, &str, &str, bool); 11] = [
+        (
+            "d69c3509bb99e412e68b0fe8544e72837dfa30746d8be2aa65975f29d22dc7b9",
+            "4df3c3f68fcc83b27e9d42c90431a72499f17875c81a599b566c9889b9696703",
+            "00000000000000000000003b78ce563f89a0ed9414f5aa28ad0d96d6795f9c6376afb1548af603b3eb45c9f8207dee1060cb71c04e80f593060b07d28308d7f4",
+            true,
+        ),
+        // Public key not on the curve
+        (
+            "eefdea4cdb677750a420fee807eacf21eb9898ae79b9768766e4faa04a2d4a34",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e17776969e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+            false,
+        ),
+        // R has an odd y
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "fff97bd5755eeea420453a14355235d382f6472f8568a18b2f057a14602975563cc27944640ac607cd107ae10923d9ef7a73c643e166be5ebeafa34b1ac553e2",
+            false,
+        ),
+        // Negated message (n - m)
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "1fa62e331edbc21c394792d2ab1100a7b432b013df3f6ff4f99fcb33e0e1515f28890b3edb6e7189b630448b515ce4f8622a954cfe545735aaea5134fccdb2bd",
+            false,
+        ),
+        // Negated s
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769961764b3aa9b2ffcb6ef947b6887a226e8d7c93e00c5ed0c1834ff0d0c2e6da6",
+            false,
+        ),
+        // s * G - e * P is infinite, x(inf) as 0
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "0000000000000000000000000000000000000000000000000000000000000000123dda8328af9c23a94c1feecfd123ba4fb73476f0d594dcb65c6425bd186051",
+            false,
+        ),
+        // s * G - e * P is infinite, x(inf) as 1
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "00000000000000000000000000000000000000000000000000000000000000017615fbaf5ae28864013c099742deadb4dba87f11ac6754f93780d5a1837cf197",
+            false,
+        ),
+        // r not an x coordinate on the curve
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "4a298dacae57395a15d0795ddbfd1dcb564da82b0f269bc70a74f8220429ba1d69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+            false,
+        ),
+        // r equal to the field size
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f69e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+            false,
+        ),
+        // s equal to the group order
+        (
+            "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e177769fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141",
+            false,
+        ),
+        // Public key exceeding the field size
+        (
+            "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc30",
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            "6cff5c3ba86c69ea4b7376f31a9bcb4f74c1976089b2d9963da2e5543e17776969e89b4c5564d00349106b8497785dd7d1d713a8ae82b32fa79d5f7fc407d39b",
+            false,
+        ),
+    ];
+
+    fn decode(hex: &str) -> Vec<u8> {
+        hex::decode(hex).unwrap()
+    }
+
+    fn verify(pk: &str, msg: &[u8], sig: &[u8]) -> bool {
+        VerifyingKey::from_bytes(&decode(pk))
+            .is_ok_and(|key| key.verify(msg, &Signature::from_bytes(sig).unwrap()))
+    }
+
+    #[test]
+    fn sign_vectors() {
+        for (sk, pk, aux, msg, sig) in SIGN_VECTORS {
+            let key = SigningKey::from_bytes(&decode(sk)).unwrap();
+            let aux = decode(aux).try_into().unwrap();
+
+            let signature = key.sign(decode(msg), &aux).unwrap();
+
+            assert_eq!(hex::encode(key.verifying_key().to_bytes()), pk);
+            assert_eq!(hex::encode(signature.to_bytes()), sig);
+            assert!(verify(pk, &decode(msg), &decode(sig)));
+        }
+    }
+
+    #[test]
+    fn verify_vectors() {
+        for (pk, msg, sig, result) in VERIFY_VECTORS {
+            assert_eq!(verify(pk, &decode(msg), &decode(sig)), result);
+        }
+        let (_, _, sig, _) = VERIFY_VECTORS[0];
+        assert!(Signature::from_bytes(&decode(sig)[1..]).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_bytes() {
+        let (_, pk, _, _, sig) = SIGN_VECTORS[1];
+        let key = VerifyingKey::from_bytes(&decode(pk)).unwrap();
+        let signature = Signature::from_bytes(&decode(sig)).unwrap();
+
+        let json = serde_json::to_string(&(&key, signature)).unwrap();
+        assert_eq!(json, format!(r#"["{pk}","{sig}"]"#));
+        let res: (VerifyingKey, Signature) = serde_json::from_str(&json).unwrap();
+        assert_eq!(res, (key.clone(), signature));
+        let cbor = serde_cbor::to_vec(&signature).unwrap();
+        assert_eq!(
+            serde_cbor::from_slice::<Signature>(&cbor).unwrap(),
+            signature
+        );
+
+        let short = format!(r#""{}""#, &sig[2..]);
+        assert!(serde_json::from_str::<Signature>(&short).is_err());
+        // Not on the curve
+        let (bad_pk, ..) = VERIFY_VECTORS[1];
+        assert!(serde_json::from_str::<VerifyingKey>(&format!(r#""{bad_pk}""#)).is_err());
+    }
+
+    #[test]
+    fn batch_vectors() {
+        let keys: Vec<_> = SIGN_VECTORS
+            .iter()
+            .map(|(_, pk, ..)| VerifyingKey::from_bytes(&decode(pk)).unwrap())
+            .collect();
+        let msgs: Vec<_> = SIGN_VECTORS.iter().map(|v| decode(v.3)).collect();
+        let sigs: Vec<_> = SIGN_VECTORS
+            .iter()
+            .map(|v| Signature::from_bytes(&decode(v.4)).unwrap())
+            .collect();
+        let mut items: Vec<BatchItem> = (0..keys.len())
+            .map(|i| (&keys[i], &msgs[i][..], &sigs[i]))
+            .collect();
+
+        assert!(verify_batch(&items));
+        assert!(verify_batch(&items[..1]));
+        assert!(verify_batch(&[]));
+
+        // R has an odd y
+        let (pk, msg, sig, _) = VERIFY_VECTORS[2];
+        let (key, msg) = (VerifyingKey::from_bytes(&decode(pk)).unwrap(), decode(msg));
+        let sig = Signature::from_bytes(&decode(sig)).unwrap();
+        items.push((&key, &msg, &sig));
+        assert!(!verify_batch(&items));
+        let mut expected = vec![true; items.len()];
+        *expected.last_mut().unwrap() = false;
+        assert_eq!(verify_batch_each(&items), expected);
+    }
+
+    #[test]
+    fn batch_results() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let keys: Vec<_> = (0..40).map(|_| SigningKey::random(&mut rng)).collect();
+        let msgs: Vec<_> = (0..keys.len()).map(|i| format!("device {i}")).collect();
+        let sigs: Vec<_> = keys
+            .iter()
+            .zip(&msgs)
+            .map(|(key, msg)| key.sign_with_rng(msg, &mut rng).unwrap())
+            .collect();
+        let mut items: Vec<BatchItem> = (0..keys.len())
+            .map(|i| (keys[i].verifying_key(), msgs[i].as_bytes(), &sigs[i]))
+            .collect();
+
+        assert!(verify_batch(&items));
+        assert!(verify_batch_each(&items).iter().all(|&ok| ok));
+
+        items[3].1 = b"tampered";
+        items[25].2 = &sigs[26];
+        assert!(!verify_batch(&items));
+        let res = verify_batch_each(&items);
+        let failed: Vec<_> = (0..res.len()).filter(|&i| !res[i]).collect();
+        assert_eq!(failed, [3, 25]);
+    }
+
+    #[test]
+    fn even_y_keys() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+
+        for _ in 0..8 {
+            let key = SigningKey::random(&mut rng);
+            let point = key.verifying_key().point();
+
+            assert!(!point.y().is_odd());
+            assert_eq!(&group().mul_generator(&key.d), point);
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! Serde support, enabled by the `serde` feature.
+//!
+//! Human-readable formats (e.g. JSON) encode integers and byte strings as
+//! lowercase hex strings, binary formats (e.g. CBOR) as big-endian bytes.
+
+use crate::{mpi::Mpi, uint::Uint};
+use core::fmt;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser, Deserialize, Deserializer, Serialize, Serializer,
+};
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>, String> {
+    if !s.len().is_multiple_of(2) || !s.is_ascii() {
+        return Err("Invalid hex string".into());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| "Invalid hex string".into()))
+        .collect()
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a byte string")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut res = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(b) = seq.next_element()? {
+            res.push(b);
+        }
+        Ok(res)
+    }
+}
+
+// Reads either a hex string or a byte string depending on the format.
+fn deserialize_bytes<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+    if d.is_human_readable() {
+        let s = String::deserialize(d)?;
+        from_hex(&s).map_err(de::Error::custom)
+    } else {
+        d.deserialize_byte_buf(BytesVisitor)
+    }
+}
+
+/// Byte strings such as digests, tags and encoded keys or signatures.
+///
+/// Use on fields with `#[serde(with = "cry_rs::serde::bytes")]`. Works with
+/// any type which is `AsRef<[u8]>` and `TryFrom<Vec<u8>>`, e.g. `Vec<u8>` and
+/// `[u8; N]`; for arrays the length is checked on deserialization.
+pub mod bytes {
+    use super::*;
+
+    pub fn serialize<T, S>(value: &T, s: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+        S: Serializer,
+    {
+        if s.is_human_readable() {
+            s.serialize_str(&to_hex(value.as_ref()))
+        } else {
+            s.serialize_bytes(value.as_ref())
+        }
+    }
+
+    pub fn deserialize<'de, T, D>(d: D) -> Result<T, D::Error>
+    where
+        T: TryFrom<Vec<u8>>,
+        D: Deserializer<'de>,
+    {
+        let bytes = deserialize_bytes(d)?;
+        let len = bytes.len();
+        T::try_from(bytes).map_err(|_| de::Error::invalid_length(len, &"a valid byte length"))
+    }
+}
+
+/// Signed hex string or, for binary formats, the big-endian magnitude.
+///
+/// Binary formats have no room for the sign, negative values are rejected.
+impl Serialize for Mpi {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        if s.is_human_readable() {
+            s.serialize_str(&self.to_str_radix(16))
+        } else if self.is_negative() {
+            Err(ser::Error::custom("Negative Mpi in a binary format"))
+        } else {
+            s.serialize_bytes(&self.to_bytes_be())
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Mpi {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        if d.is_human_readable() {
+            let s = String::deserialize(d)?;
+            Mpi::from_str_radix(&s, 16).map_err(de::Error::custom)
+        } else {
+            d.deserialize_byte_buf(BytesVisitor)
+                .map(|bytes| Mpi::from_bytes_be(&bytes))
+        }
+    }
+}
+
+/// Fixed width big-endian encoding, `BYTES` long.
+impl<const LIMBS: usize> Serialize for Uint<LIMBS> {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        let mut buf = vec![0; Self::BYTES];
+        self.write_be_bytes(&mut buf);
+        bytes::serialize(&buf, s)
+    }
+}
+
+impl<'de, const LIMBS: usize> Deserialize<'de> for Uint<LIMBS> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let buf = deserialize_bytes(d)?;
+        Self::from_be_slice(&buf).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sha256::Sha256, uint::U256};
+    use serde_test::{assert_de_tokens_error, assert_ser_tokens_error, assert_tokens};
+    use serde_test::{Configure, Token};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct State {
+        counter: Mpi,
+        #[serde(with = "bytes")]
+        digest: [u8; 32],
+        #[serde(with = "bytes")]
+        tag: Vec<u8>,
+    }
+
+    fn state() -> State {
+        let mut h = Sha256::new();
+        h.update(b"abc");
+        State {
+            counter: Mpi::from(-255i64),
+            digest: h.digest(),
+            tag: vec![0xde, 0xad, 0xbe, 0xef],
+        }
+    }
+
+    #[test]
+    fn mpi_tokens() {
+        let a = Mpi::from_hex("123456789abcdef0").unwrap();
+
+        assert_tokens(&a.clone().readable(), &[Token::Str("123456789abcdef0")]);
+        assert_tokens(
+            &a.compact(),
+            &[Token::Bytes(&[
+                0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde, 0xf0,
+            ])],
+        );
+        assert_tokens(&Mpi::from(-1i64).readable(), &[Token::Str("-1")]);
+        assert_tokens(&Mpi::new().compact(), &[Token::Bytes(&[])]);
+    }
+
+    #[test]
+    fn mpi_errors() {
+        assert_ser_tokens_error(
+            &Mpi::from(-1i64).compact(),
+            &[],
+            "Negative Mpi in a binary format",
+        );
+        assert_de_tokens_error::<serde_test::Readable<Mpi>>(
+            &[Token::Str("xyz")],
+            "Invalid radix 16 string",
+        );
+    }
+
+    #[test]
+    fn uint_tokens() {
+        const BYTES: [u8; 32] = [
+            0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0x12, 0x34,
+        ];
+        let a = U256::from(0x1234);
+
+        assert_tokens(&a.compact(), &[Token::Bytes(&BYTES)]);
+        assert_tokens(
+            &a.readable(),
+            &[Token::Str(
+                "0000000000000000000000000000000000000000000000000000000000001234",
+            )],
+        );
+    }
+
+    #[test]
+    fn bytes_length_checked() {
+        let json = r#"{"counter":"1","digest":"00","tag":""}"#;
+
+        assert!(serde_json::from_str::<State>(json).is_err());
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let state = state();
+
+        let json = serde_json::to_string(&state).unwrap();
+
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"counter":"-ff","#,
+                r#""digest":"ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad","#,
+                r#""tag":"deadbeef"}"#
+            )
+        );
+        assert_eq!(serde_json::from_str::<State>(&json).unwrap(), state);
+    }
+
+    #[test]
+    fn cbor_roundtrip() {
+        let state = State {
+            counter: Mpi::from(255i64),
+            ..state()
+        };
+
+        let cbor = serde_cbor::to_vec(&state).unwrap();
+
+        assert_eq!(serde_cbor::from_slice::<State>(&cbor).unwrap(), state);
+    }
+}
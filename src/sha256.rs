@@ -89,4 +89,14 @@ mod test {
             "872e4e50ce9990d8b041330c47c9ddd11bec6b503ae9386a99da8584e9bb12c4"
         );
     }
+
+    #[test]
+    fn digest_parts() {
+        let digest = Sha256::digest_parts(&[b"Hello", b"World"]);
+
+        assert_eq!(
+            hex::encode(digest),
+            "872e4e50ce9990d8b041330c47c9ddd11bec6b503ae9386a99da8584e9bb12c4"
+        );
+    }
 }
@@ -165,4 +165,14 @@ mod test {
             "293cd96eb25228a6fb09bfa86b9148ab69940e68903cbc0527a4fb150eec1ebe0f1ffce0bc5e3df312377e0a68f1950a"
         );
     }
+
+    #[test]
+    fn sha384_digest_parts() {
+        let digest = Sha384::digest_parts(&[b"Hello", b"World"]);
+
+        assert_eq!(
+            hex::encode(digest),
+            "293cd96eb25228a6fb09bfa86b9148ab69940e68903cbc0527a4fb150eec1ebe0f1ffce0bc5e3df312377e0a68f1950a"
+        );
+    }
 }
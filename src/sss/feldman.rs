@@ -0,0 +1,124 @@
+use super::{Shamir, Share};
+use crate::{
+    ec::{Group, Point},
+    mpi::Mpi,
+};
+use rand_core::{CryptoRng, RngCore};
+
+/// Feldman verifiable sharing, over the scalars modulo the order of an EC
+/// group.
+///
+/// Along with the shares the dealer publishes the commitments `a_j * G` to
+/// the polynomial coefficients, which let every holder check their own share.
+/// The first commitment is `s * G`, so the secret must not be guessable.
+#[derive(Clone, Debug)]
+pub struct Feldman {
+    group: Group,
+    shamir: Shamir,
+}
+
+impl Feldman {
+    pub fn new(group: Group) -> Self {
+        // The group order is prime, as checked on the group construction.
+        let shamir = Shamir {
+            p: group.order().clone(),
+        };
+        Feldman { group, shamir }
+    }
+
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    /// Shares of `secret`, which must be in `[0, n)`, with the commitments
+    /// to the sharing polynomial.
+    pub fn split<R: RngCore + CryptoRng>(
+        &self,
+        secret: &Mpi,
+        threshold: usize,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<(Vec<Share>, Vec<Point>), String> {
+        let coeffs = self.shamir.polynomial(secret, threshold, count, rng)?;
+        let commitments = coeffs.iter().map(|a| self.group.mul_generator(a)).collect();
+        Ok((self.shamir.shares(&coeffs, count), commitments))
+    }
+
+    /// Checks `y * G = C_0 + x * C_1 + ... + x^(t-1) * C_(t-1)`.
+    pub fn verify(&self, share: &Share, commitments: &[Point]) -> bool {
+        let n = self.group.order();
+        if commitments.is_empty()
+            || share.y.is_negative()
+            || &share.y >= n
+            || !commitments.iter().all(|c| self.group.is_on_curve(c))
+        {
+            return false;
+        }
+        let x = Mpi::from(u64::from(share.x));
+        let mut x_pow = Mpi::from(1i64);
+        let mut terms = Vec::with_capacity(commitments.len());
+        for c in commitments {
+            terms.push((x_pow.clone(), c.clone()));
+            x_pow = (&x_pow * &x) % n;
+        }
+        // -y * G + sum(x^j * C_j) = O
+        self.group
+            .generator_table()
+            .mul_add(&share.y.neg(), &terms)
+            .is_identity()
+    }
+
+    pub fn combine(&self, shares: &[Share]) -> Result<Mpi, String> {
+        self.shamir.combine(shares)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::{toy_group, Curve};
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn verifiable_shares() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        for curve in [Curve::Secp256k1, Curve::Secp384r1] {
+            let feldman = Feldman::new(Group::new(curve));
+            let secret = Mpi::random_below(feldman.group().order(), &mut rng);
+
+            let (shares, commitments) = feldman.split(&secret, 3, 5, &mut rng).unwrap();
+            assert_eq!(commitments.len(), 3);
+            assert_eq!(commitments[0], feldman.group().mul_generator(&secret));
+            assert!(shares.iter().all(|s| feldman.verify(s, &commitments)));
+            assert_eq!(feldman.combine(&shares[1..4]).unwrap(), secret);
+        }
+    }
+
+    #[test]
+    fn invalid_shares() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let int = |x: i64| Mpi::from(x);
+        let grp = toy_group();
+        let feldman = Feldman::new(grp);
+
+        let (shares, commitments) = feldman.split(&int(1234), 2, 4, &mut rng).unwrap();
+        assert!(shares.iter().all(|s| feldman.verify(s, &commitments)));
+        assert_eq!(feldman.combine(&shares[2..]).unwrap(), int(1234));
+
+        let share = &shares[1];
+        let bad_y = Share::new(share.x(), (share.y() + 1u64) % 2521u64);
+        assert!(!feldman.verify(&bad_y, &commitments));
+        let bad_x = Share::new(3, share.y().clone());
+        assert!(!feldman.verify(&bad_x, &commitments));
+        let unreduced = Share::new(share.x(), share.y() + 2521u64);
+        assert!(!feldman.verify(&unreduced, &commitments));
+
+        assert!(!feldman.verify(share, &commitments[..1]));
+        assert!(!feldman.verify(share, &[]));
+        let swapped = [commitments[1].clone(), commitments[0].clone()];
+        assert!(!feldman.verify(share, &swapped));
+        let off_curve = [commitments[0].clone(), Point::new(int(1), int(1))];
+        assert!(!feldman.verify(share, &off_curve));
+        assert!(feldman.split(&int(2521), 2, 4, &mut rng).is_err());
+    }
+}
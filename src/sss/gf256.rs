@@ -0,0 +1,196 @@
+//! Byte-wise sharing over `GF(2^8)`, for secrets of any length.
+//!
+//! Each byte of the secret is shared with its own polynomial, all evaluated
+//! at the same `x`. The field is the AES one, modulo `x^8 + x^4 + x^3 + x + 1`.
+
+use rand_core::{CryptoRng, RngCore};
+
+/// Evaluations at `x` of the polynomials of each secret byte.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ByteShare {
+    x: u8,
+    y: Vec<u8>,
+}
+
+impl ByteShare {
+    pub fn new(x: u8, y: Vec<u8>) -> Self {
+        ByteShare { x, y }
+    }
+
+    pub fn x(&self) -> u8 {
+        self.x
+    }
+
+    pub fn y(&self) -> &[u8] {
+        &self.y
+    }
+
+    /// `x` followed by `y`, one byte longer than the secret.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.y.len() + 1);
+        out.push(self.x);
+        out.extend_from_slice(&self.y);
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        match bytes.split_first() {
+            Some((&x, y)) if x != 0 => Ok(ByteShare::new(x, y.to_vec())),
+            _ => Err("Invalid share encoding".into()),
+        }
+    }
+}
+
+// Carry-less product reduced modulo 0x11b, without data dependent branches.
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut res = 0;
+    for _ in 0..8 {
+        res ^= a & 0u8.wrapping_sub(b & 1);
+        let carry = 0u8.wrapping_sub(a >> 7);
+        a = (a << 1) ^ (carry & 0x1b);
+        b >>= 1;
+    }
+    res
+}
+
+// a^254 = a^-1, with 0 mapped to 0. The exponent is fixed, so the running
+// time doesn't depend on `a`.
+fn inv(a: u8) -> u8 {
+    (0..8).rev().fold(1, |acc, i| {
+        let acc = mul(acc, acc);
+        if (254 >> i) & 1 == 1 {
+            mul(acc, a)
+        } else {
+            acc
+        }
+    })
+}
+
+/// Splits `secret` into `count` shares, at `x = 1, ..., count`, any
+/// `threshold` of them recover it.
+pub fn split_bytes<R: RngCore + CryptoRng>(
+    secret: &[u8],
+    threshold: u8,
+    count: u8,
+    rng: &mut R,
+) -> Result<Vec<ByteShare>, String> {
+    if threshold == 0 || threshold > count {
+        return Err("Invalid threshold".into());
+    }
+    // coeffs[i] holds the coefficients of degree i + 1 of all the bytes.
+    let coeffs: Vec<_> = (1..threshold)
+        .map(|_| {
+            let mut c = vec![0; secret.len()];
+            rng.fill_bytes(&mut c);
+            c
+        })
+        .collect();
+    let shares = (1..=count)
+        .map(|x| {
+            let y = (0..secret.len())
+                .map(|b| {
+                    // Horner's rule, the constant term is the secret byte
+                    let high = coeffs.iter().rev().fold(0, |acc, c| mul(acc ^ c[b], x));
+                    high ^ secret[b]
+                })
+                .collect();
+            ByteShare::new(x, y)
+        })
+        .collect();
+    Ok(shares)
+}
+
+/// Secret from at least `threshold` shares. As for prime field shares, using
+/// fewer shares gives a meaningless result.
+pub fn combine_bytes(shares: &[ByteShare]) -> Result<Vec<u8>, String> {
+    let len = match shares.first() {
+        Some(share) => share.y.len(),
+        None => return Err("No shares".into()),
+    };
+    if shares.iter().any(|s| s.x == 0 || s.y.len() != len) {
+        return Err("Invalid share".into());
+    }
+    // Lagrange basis at 0, prod(x_j / (x_j - x_i)), subtraction being xor.
+    let mut basis = Vec::with_capacity(shares.len());
+    for (i, share) in shares.iter().enumerate() {
+        let mut num = 1;
+        let mut den = 1;
+        for (j, other) in shares.iter().enumerate() {
+            if i != j {
+                if other.x == share.x {
+                    return Err("Duplicate share index".into());
+                }
+                num = mul(num, other.x);
+                den = mul(den, other.x ^ share.x);
+            }
+        }
+        basis.push(mul(num, inv(den)));
+    }
+    let secret = (0..len)
+        .map(|b| {
+            shares
+                .iter()
+                .zip(&basis)
+                .fold(0, |acc, (share, &l)| acc ^ mul(share.y[b], l))
+        })
+        .collect();
+    Ok(secret)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn field_arithmetic() {
+        // FIPS-197, 4.2
+        assert_eq!(mul(0x57, 0x83), 0xc1);
+        assert_eq!(mul(0x57, 0x13), 0xfe);
+        assert_eq!(inv(0x53), 0xca);
+        assert_eq!(inv(0), 0);
+        assert!((1..=255).all(|a| mul(a, inv(a)) == 1));
+    }
+
+    #[test]
+    fn split_combine() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let secret = b"correct horse battery staple, 32";
+
+        let shares = split_bytes(secret, 3, 5, &mut rng).unwrap();
+        assert!(shares.iter().all(|s| s.y().len() == secret.len()));
+        assert_eq!(combine_bytes(&shares[..3]).unwrap(), secret);
+        assert_eq!(combine_bytes(&shares[2..]).unwrap(), secret);
+        assert_eq!(combine_bytes(&shares).unwrap(), secret);
+        assert_ne!(combine_bytes(&shares[1..3]).unwrap(), secret);
+
+        let shares = split_bytes(secret, 255, 255, &mut rng).unwrap();
+        assert_eq!(combine_bytes(&shares).unwrap(), secret);
+        assert_eq!(combine_bytes(&[]).unwrap_err(), "No shares");
+        assert!(split_bytes(b"", 2, 3, &mut rng).unwrap()[0].y().is_empty());
+    }
+
+    #[test]
+    fn invalid_params() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        assert!(split_bytes(b"secret", 0, 3, &mut rng).is_err());
+        assert!(split_bytes(b"secret", 4, 3, &mut rng).is_err());
+
+        let shares = split_bytes(b"secret", 2, 3, &mut rng).unwrap();
+        let dup = [shares[0].clone(), shares[0].clone()];
+        assert!(combine_bytes(&dup).is_err());
+        let short = ByteShare::new(3, b"secre".to_vec());
+        assert!(combine_bytes(&[shares[0].clone(), short]).is_err());
+    }
+
+    #[test]
+    fn share_encoding() {
+        let share = ByteShare::new(2, vec![0xde, 0xad]);
+        let bytes = share.to_bytes();
+
+        assert_eq!(bytes, [2, 0xde, 0xad]);
+        assert_eq!(ByteShare::from_bytes(&bytes).unwrap(), share);
+        assert!(ByteShare::from_bytes(&[]).is_err());
+        assert!(ByteShare::from_bytes(&[0, 1]).is_err());
+    }
+}
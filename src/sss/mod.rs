@@ -0,0 +1,226 @@
+//! Shamir secret sharing.
+//!
+//! A secret is split into `count` shares such that any `threshold` of them
+//! recover it, while fewer reveal nothing. Shares are evaluations of a random
+//! polynomial of degree `threshold - 1` whose constant term is the secret.
+
+use crate::mpi::Mpi;
+use rand_core::{CryptoRng, RngCore};
+
+mod feldman;
+mod gf256;
+
+pub use feldman::Feldman;
+pub use gf256::{combine_bytes, split_bytes, ByteShare};
+
+/// Evaluation `y` of the sharing polynomial at `x`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Share {
+    x: u32,
+    y: Mpi,
+}
+
+impl Share {
+    pub fn new(x: u32, y: Mpi) -> Self {
+        Share { x, y }
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> &Mpi {
+        &self.y
+    }
+
+    /// `x` as 4 bytes big-endian followed by `y` big-endian.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = self.x.to_be_bytes().to_vec();
+        out.extend(self.y.to_bytes_be());
+        out
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 4 {
+            return Err("Invalid share length".into());
+        }
+        let (x, y) = bytes.split_at(4);
+        let x = u32::from_be_bytes(x.try_into().expect("4 bytes"));
+        Ok(Share::new(x, Mpi::from_bytes_be(y)))
+    }
+}
+
+/// Sharing over the prime field `GF(p)`, the secret must be smaller than `p`.
+#[derive(Clone, Debug)]
+pub struct Shamir {
+    p: Mpi,
+}
+
+impl Shamir {
+    pub fn new(p: Mpi) -> Result<Self, String> {
+        if !p.is_prime() {
+            return Err("Field modulus not prime".into());
+        }
+        Ok(Shamir { p })
+    }
+
+    pub fn prime(&self) -> &Mpi {
+        &self.p
+    }
+
+    /// Splits `secret` into `count` shares, at `x = 1, ..., count`.
+    pub fn split<R: RngCore + CryptoRng>(
+        &self,
+        secret: &Mpi,
+        threshold: usize,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<Vec<Share>, String> {
+        let coeffs = self.polynomial(secret, threshold, count, rng)?;
+        Ok(self.shares(&coeffs, count))
+    }
+
+    /// Secret from at least `threshold` shares. With fewer shares the result
+    /// is unrelated to the secret, there's no way to detect it.
+    pub fn combine(&self, shares: &[Share]) -> Result<Mpi, String> {
+        if shares.is_empty() {
+            return Err("No shares".into());
+        }
+        let p = &self.p;
+        let xs: Vec<_> = shares.iter().map(|s| Mpi::from(u64::from(s.x))).collect();
+        if xs.iter().any(|x| (x % p).is_zero()) {
+            return Err("Invalid share index".into());
+        }
+        // Lagrange interpolation at 0: sum(y_i * prod(x_j / (x_j - x_i)))
+        let mut secret = Mpi::new();
+        for (i, share) in shares.iter().enumerate() {
+            let mut num = Mpi::from(1i64);
+            let mut den = Mpi::from(1i64);
+            for (j, x_j) in xs.iter().enumerate() {
+                if i != j {
+                    num = (&num * x_j) % p;
+                    den = (&den * &(x_j - &xs[i])).rem_euclid(p);
+                }
+            }
+            let den_inv = den.mod_inv(p).ok_or("Duplicate share index")?;
+            let term = &(&share.y * &num) % p;
+            secret = (&secret + &(&term * &den_inv)) % p;
+        }
+        Ok(secret.rem_euclid(p))
+    }
+
+    // Coefficients of a random polynomial with the secret as constant term.
+    fn polynomial<R: RngCore + CryptoRng>(
+        &self,
+        secret: &Mpi,
+        threshold: usize,
+        count: usize,
+        rng: &mut R,
+    ) -> Result<Vec<Mpi>, String> {
+        if threshold == 0 || threshold > count {
+            return Err("Invalid threshold".into());
+        }
+        if Mpi::from(count as u64) >= self.p {
+            return Err("Too many shares".into());
+        }
+        if secret.is_negative() || secret >= &self.p {
+            return Err("Secret out of the field".into());
+        }
+        let mut coeffs = vec![secret.clone()];
+        coeffs.extend((1..threshold).map(|_| Mpi::random_below(&self.p, rng)));
+        Ok(coeffs)
+    }
+
+    // Evaluations at `x = 1, ..., count`.
+    fn shares(&self, coeffs: &[Mpi], count: usize) -> Vec<Share> {
+        (1..=count as u32)
+            .map(|x| {
+                // Horner's rule
+                let x_mpi = Mpi::from(u64::from(x));
+                let y = coeffs
+                    .iter()
+                    .rev()
+                    .fold(Mpi::new(), |acc, c| (&(&acc * &x_mpi) + c) % &self.p);
+                Share::new(x, y)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    fn share(x: u32, y: i64) -> Share {
+        Share::new(x, Mpi::from(y))
+    }
+
+    #[test]
+    fn textbook_example() {
+        // f(x) = 1234 + 166x + 94x^2 mod 1613
+        let shamir = Shamir::new(Mpi::from(1613i64)).unwrap();
+        let coeffs = [1234i64, 166, 94].map(Mpi::from);
+
+        let shares = shamir.shares(&coeffs, 6);
+        let ys: Vec<_> = shares
+            .iter()
+            .map(|s| i64::try_from(s.y()).unwrap())
+            .collect();
+        assert_eq!(ys, [1494, 329, 965, 176, 1188, 775]);
+
+        let subset = [share(2, 329), share(4, 176), share(5, 1188)];
+        assert_eq!(shamir.combine(&subset).unwrap(), Mpi::from(1234i64));
+        assert_ne!(shamir.combine(&subset[..2]).unwrap(), Mpi::from(1234i64));
+    }
+
+    #[test]
+    fn split_combine() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        // 2^127 - 1
+        let p = Mpi::from(1i64).shl(127) - 1u64;
+        let shamir = Shamir::new(p).unwrap();
+        let secret = Mpi::from_hex("0123456789abcdef0123456789abcdef").unwrap();
+
+        let shares = shamir.split(&secret, 3, 5, &mut rng).unwrap();
+        assert_eq!(shares.len(), 5);
+        let picked = [shares[4].clone(), shares[0].clone(), shares[2].clone()];
+        for subset in [&shares[..3], &shares[2..], &picked] {
+            assert_eq!(shamir.combine(subset).unwrap(), secret);
+        }
+        assert_eq!(shamir.combine(&shares).unwrap(), secret);
+        assert_ne!(shamir.combine(&shares[..2]).unwrap(), secret);
+
+        let single = shamir.split(&secret, 1, 3, &mut rng).unwrap();
+        assert!(single.iter().all(|s| s.y() == &secret));
+    }
+
+    #[test]
+    fn invalid_params() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        assert!(Shamir::new(Mpi::from(1611i64)).is_err());
+        let shamir = Shamir::new(Mpi::from(13i64)).unwrap();
+        let secret = Mpi::from(5i64);
+
+        assert!(shamir.split(&secret, 0, 3, &mut rng).is_err());
+        assert!(shamir.split(&secret, 4, 3, &mut rng).is_err());
+        assert!(shamir.split(&secret, 2, 13, &mut rng).is_err());
+        assert!(shamir.split(&Mpi::from(13i64), 2, 3, &mut rng).is_err());
+        assert!(shamir.split(&Mpi::from(-1i64), 2, 3, &mut rng).is_err());
+        assert!(shamir.split(&secret, 2, 12, &mut rng).is_ok());
+
+        assert!(shamir.combine(&[]).is_err());
+        assert!(shamir.combine(&[share(1, 3), share(1, 4)]).is_err());
+        assert!(shamir.combine(&[share(13, 3), share(1, 4)]).is_err());
+    }
+
+    #[test]
+    fn share_encoding() {
+        let share = Share::new(7, Mpi::from_hex("abcdef").unwrap());
+        let bytes = share.to_bytes();
+
+        assert_eq!(hex::encode(&bytes), "00000007abcdef");
+        assert_eq!(Share::from_bytes(&bytes).unwrap(), share);
+        assert!(Share::from_bytes(&bytes[..3]).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use core::{ffi::c_void, mem::MaybeUninit};
 use cry_sys::bindings::{cry_ciph_itf, cry_hash_itf};
 use typenum::Unsigned;
 
@@ -8,9 +9,53 @@ pub trait Cipher {
     fn interface() -> *const cry_ciph_itf;
 }
 
-pub trait Hasher {
+// Largest output written by a backend digest function. Truncated variants
+// (e.g. SHA-384) share the full width digest of the base function.
+const MAX_BACKEND_DIGEST_LEN: usize = 64;
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl Sealed for crate::sha1::Sha1 {}
+    impl Sealed for crate::sha256::Sha256 {}
+    impl Sealed for crate::sha512::Sha384 {}
+    impl Sealed for crate::sha512::Sha512 {}
+    #[cfg(feature = "weak")]
+    impl Sealed for crate::md5::Md5 {}
+}
+
+/// Hash function of the crate, the parameter of the generic constructions
+/// (HMAC, RSA paddings, ECDSA nonces).
+///
+/// The trait is sealed: the implementations drive the backend through raw
+/// pointers, so only the hashes of this crate implement it.
+pub trait Hasher: sealed::Sealed {
     type Backend;
     type DigestLen: Unsigned;
 
     fn interface() -> *const cry_hash_itf;
+
+    /// One-shot digest of the concatenation of `parts`, driven by the
+    /// backend generic interface.
+    fn digest_parts(parts: &[&[u8]]) -> Vec<u8> {
+        let itf = unsafe { &*Self::interface() };
+        let mut backend = MaybeUninit::<Self::Backend>::zeroed();
+        let ctx = backend.as_mut_ptr() as *mut c_void;
+        let mut digest = vec![0; MAX_BACKEND_DIGEST_LEN];
+        unsafe {
+            if let Some(init) = itf.init {
+                init(ctx);
+            }
+            let update = itf.update.expect("Missing hash update");
+            for part in parts {
+                update(ctx, part.as_ptr(), part.len() as u64);
+            }
+            itf.digest.expect("Missing hash digest")(ctx, digest.as_mut_ptr());
+            if let Some(clear) = itf.clear {
+                clear(ctx);
+            }
+        }
+        digest.truncate(Self::DigestLen::USIZE);
+        digest
+    }
 }
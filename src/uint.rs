@@ -0,0 +1,549 @@
+//! Stack allocated fixed width unsigned integers.
+//!
+//! Values are stored as little-endian 64-bit limbs, the same digit size used by
+//! the `mpi` backend. Unless stated otherwise, operations run in constant time
+//! with respect to the operands values. This module doesn't depend on the
+//! standard library, the conversions to and from `Mpi` are available with the
+//! `std` feature.
+
+use core::cmp::Ordering;
+
+type Limb = u64;
+type Wide = u128;
+
+const LIMB_BITS: usize = Limb::BITS as usize;
+const LIMB_BYTES: usize = LIMB_BITS / 8;
+
+/// Unsigned integer with `LIMBS` 64-bit limbs.
+#[derive(Clone, Copy, Debug)]
+pub struct Uint<const LIMBS: usize> {
+    limbs: [Limb; LIMBS],
+}
+
+pub type U192 = Uint<3>;
+pub type U256 = Uint<4>;
+pub type U384 = Uint<6>;
+pub type U512 = Uint<8>;
+pub type U576 = Uint<9>;
+
+// All ones if `choice` is set, zero otherwise.
+#[inline]
+fn mask(choice: Limb) -> Limb {
+    choice.wrapping_neg()
+}
+
+#[inline]
+fn adc(a: Limb, b: Limb, carry: Limb) -> (Limb, Limb) {
+    let t = a as Wide + b as Wide + carry as Wide;
+    (t as Limb, (t >> LIMB_BITS) as Limb)
+}
+
+#[inline]
+fn sbb(a: Limb, b: Limb, borrow: Limb) -> (Limb, Limb) {
+    let t = (a as Wide).wrapping_sub(b as Wide + borrow as Wide);
+    (t as Limb, (t >> (2 * LIMB_BITS - 1)) as Limb)
+}
+
+// Returns `a * b + c + carry` as (low, high).
+#[inline]
+fn mac(a: Limb, b: Limb, c: Limb, carry: Limb) -> (Limb, Limb) {
+    let t = a as Wide * b as Wide + c as Wide + carry as Wide;
+    (t as Limb, (t >> LIMB_BITS) as Limb)
+}
+
+impl<const LIMBS: usize> Uint<LIMBS> {
+    pub const BITS: usize = LIMBS * LIMB_BITS;
+    pub const BYTES: usize = LIMBS * LIMB_BYTES;
+    pub const ZERO: Self = Uint { limbs: [0; LIMBS] };
+    pub const ONE: Self = Self::from_u64(1);
+    pub const MAX: Self = Uint {
+        limbs: [Limb::MAX; LIMBS],
+    };
+
+    pub const fn from_u64(val: u64) -> Self {
+        let mut limbs = [0; LIMBS];
+        if LIMBS > 0 {
+            limbs[0] = val;
+        }
+        Uint { limbs }
+    }
+
+    pub const fn from_limbs(limbs: [u64; LIMBS]) -> Self {
+        Uint { limbs }
+    }
+
+    /// Little-endian limbs.
+    pub const fn as_limbs(&self) -> &[u64; LIMBS] {
+        &self.limbs
+    }
+
+    /// Big-endian bytes, shorter inputs are zero extended.
+    pub fn from_be_slice(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() > Self::BYTES {
+            return Err("Input too long");
+        }
+        let mut res = Self::ZERO;
+        for (i, b) in bytes.iter().rev().enumerate() {
+            res.limbs[i / LIMB_BYTES] |= (*b as Limb) << (8 * (i % LIMB_BYTES));
+        }
+        Ok(res)
+    }
+
+    /// Little-endian bytes, shorter inputs are zero extended.
+    pub fn from_le_slice(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() > Self::BYTES {
+            return Err("Input too long");
+        }
+        let mut res = Self::ZERO;
+        for (i, b) in bytes.iter().enumerate() {
+            res.limbs[i / LIMB_BYTES] |= (*b as Limb) << (8 * (i % LIMB_BYTES));
+        }
+        Ok(res)
+    }
+
+    /// Writes the big-endian representation, `out` must be `BYTES` long.
+    pub fn write_be_bytes(&self, out: &mut [u8]) {
+        assert_eq!(out.len(), Self::BYTES, "Invalid output length");
+        for (chunk, limb) in out
+            .chunks_exact_mut(LIMB_BYTES)
+            .rev()
+            .zip(self.limbs.iter())
+        {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+    }
+
+    /// Writes the little-endian representation, `out` must be `BYTES` long.
+    pub fn write_le_bytes(&self, out: &mut [u8]) {
+        assert_eq!(out.len(), Self::BYTES, "Invalid output length");
+        for (chunk, limb) in out.chunks_exact_mut(LIMB_BYTES).zip(self.limbs.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+    }
+
+    /// Returns `a` if `choice` is false, `b` otherwise.
+    pub fn select(a: &Self, b: &Self, choice: bool) -> Self {
+        let m = mask(choice as Limb);
+        let mut res = Self::ZERO;
+        for i in 0..LIMBS {
+            res.limbs[i] = a.limbs[i] ^ (m & (a.limbs[i] ^ b.limbs[i]));
+        }
+        res
+    }
+
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        let acc = self
+            .limbs
+            .iter()
+            .zip(other.limbs.iter())
+            .fold(0, |acc, (a, b)| acc | (a ^ b));
+        // Maps zero to one and everything else to zero without branching.
+        (((acc | acc.wrapping_neg()) >> (LIMB_BITS - 1)) ^ 1) == 1
+    }
+
+    pub fn ct_lt(&self, other: &Self) -> bool {
+        self.overflowing_sub(other).1
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.ct_eq(&Self::ZERO)
+    }
+
+    pub fn is_odd(&self) -> bool {
+        LIMBS > 0 && self.limbs[0] & 1 == 1
+    }
+
+    /// Value of the `i`-th bit, zero if out of range.
+    pub fn bit(&self, i: usize) -> bool {
+        i < Self::BITS && (self.limbs[i / LIMB_BITS] >> (i % LIMB_BITS)) & 1 == 1
+    }
+
+    /// Position of the most significant set bit plus one.
+    ///
+    /// This is not constant time.
+    pub fn bits(&self) -> usize {
+        self.limbs.iter().rposition(|&l| l != 0).map_or(0, |i| {
+            i * LIMB_BITS + (LIMB_BITS - self.limbs[i].leading_zeros() as usize)
+        })
+    }
+
+    pub fn overflowing_add(&self, rhs: &Self) -> (Self, bool) {
+        let mut res = Self::ZERO;
+        let mut carry = 0;
+        for i in 0..LIMBS {
+            (res.limbs[i], carry) = adc(self.limbs[i], rhs.limbs[i], carry);
+        }
+        (res, carry == 1)
+    }
+
+    pub fn overflowing_sub(&self, rhs: &Self) -> (Self, bool) {
+        let mut res = Self::ZERO;
+        let mut borrow = 0;
+        for i in 0..LIMBS {
+            (res.limbs[i], borrow) = sbb(self.limbs[i], rhs.limbs[i], borrow);
+        }
+        (res, borrow == 1)
+    }
+
+    pub fn wrapping_add(&self, rhs: &Self) -> Self {
+        self.overflowing_add(rhs).0
+    }
+
+    pub fn wrapping_sub(&self, rhs: &Self) -> Self {
+        self.overflowing_sub(rhs).0
+    }
+
+    /// Full product as (low, high) halves.
+    pub fn mul_wide(&self, rhs: &Self) -> (Self, Self) {
+        let mut lo = Self::ZERO;
+        let mut hi = Self::ZERO;
+        for i in 0..LIMBS {
+            let mut carry = 0;
+            for j in 0..LIMBS {
+                let k = i + j;
+                let acc = if k < LIMBS {
+                    &mut lo.limbs[k]
+                } else {
+                    &mut hi.limbs[k - LIMBS]
+                };
+                (*acc, carry) = mac(self.limbs[i], rhs.limbs[j], *acc, carry);
+            }
+            hi.limbs[i] = carry;
+        }
+        (lo, hi)
+    }
+
+    pub fn wrapping_mul(&self, rhs: &Self) -> Self {
+        self.mul_wide(rhs).0
+    }
+
+    /// Reduces the double width value `(lo, hi)` modulo `m`.
+    ///
+    /// Binary long division, the running time only depends on `LIMBS`.
+    /// Panics if `m` is zero.
+    pub fn reduce_wide(lo: &Self, hi: &Self, m: &Self) -> Self {
+        assert!(!m.is_zero(), "Zero modulus");
+        let mut r = Self::ZERO;
+        for i in (0..2 * Self::BITS).rev() {
+            let bit = if i >= Self::BITS {
+                hi.bit(i - Self::BITS)
+            } else {
+                lo.bit(i)
+            };
+            // r = 2r + bit, r < m guarantees that one subtraction is enough.
+            let top = r.limbs.last().map_or(0, |l| l >> (LIMB_BITS - 1));
+            let mut carry = bit as Limb;
+            for l in r.limbs.iter_mut() {
+                let next = *l >> (LIMB_BITS - 1);
+                *l = (*l << 1) | carry;
+                carry = next;
+            }
+            let (d, borrow) = r.overflowing_sub(m);
+            r = Self::select(&r, &d, (top | (!borrow as Limb)) == 1);
+        }
+        r
+    }
+
+    /// `self mod m`. Panics if `m` is zero.
+    pub fn rem(&self, m: &Self) -> Self {
+        Self::reduce_wide(self, &Self::ZERO, m)
+    }
+
+    /// `(self + rhs) mod m`, operands must be reduced.
+    pub fn add_mod(&self, rhs: &Self, m: &Self) -> Self {
+        let (s, carry) = self.overflowing_add(rhs);
+        let (d, borrow) = s.overflowing_sub(m);
+        Self::select(&s, &d, ((carry as Limb) | (!borrow as Limb)) == 1)
+    }
+
+    /// `(self - rhs) mod m`, operands must be reduced.
+    pub fn sub_mod(&self, rhs: &Self, m: &Self) -> Self {
+        let (d, borrow) = self.overflowing_sub(rhs);
+        let fix = Self::select(&Self::ZERO, m, borrow);
+        d.wrapping_add(&fix)
+    }
+
+    /// `-self mod m`, operand must be reduced.
+    pub fn neg_mod(&self, m: &Self) -> Self {
+        Self::ZERO.sub_mod(self, m)
+    }
+
+    /// `(self * rhs) mod m`. Panics if `m` is zero.
+    pub fn mul_mod(&self, rhs: &Self, m: &Self) -> Self {
+        let (lo, hi) = self.mul_wide(rhs);
+        Self::reduce_wide(&lo, &hi, m)
+    }
+}
+
+impl<const LIMBS: usize> Default for Uint<LIMBS> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const LIMBS: usize> PartialEq for Uint<LIMBS> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other)
+    }
+}
+
+impl<const LIMBS: usize> Eq for Uint<LIMBS> {}
+
+impl<const LIMBS: usize> PartialOrd for Uint<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const LIMBS: usize> Ord for Uint<LIMBS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.ct_lt(other) {
+            Ordering::Less
+        } else if self.ct_eq(other) {
+            Ordering::Equal
+        } else {
+            Ordering::Greater
+        }
+    }
+}
+
+impl<const LIMBS: usize> From<u64> for Uint<LIMBS> {
+    fn from(val: u64) -> Self {
+        Self::from_u64(val)
+    }
+}
+
+// Fixed size byte arrays conversions for the common widths.
+macro_rules! impl_bytes {
+    ($($limbs:literal),*) => {$(
+        impl Uint<$limbs> {
+            pub fn from_be_bytes(bytes: [u8; $limbs * LIMB_BYTES]) -> Self {
+                Self::from_be_slice(&bytes).unwrap_or(Self::ZERO)
+            }
+
+            pub fn from_le_bytes(bytes: [u8; $limbs * LIMB_BYTES]) -> Self {
+                Self::from_le_slice(&bytes).unwrap_or(Self::ZERO)
+            }
+
+            pub fn to_be_bytes(&self) -> [u8; $limbs * LIMB_BYTES] {
+                let mut out = [0; $limbs * LIMB_BYTES];
+                self.write_be_bytes(&mut out);
+                out
+            }
+
+            pub fn to_le_bytes(&self) -> [u8; $limbs * LIMB_BYTES] {
+                let mut out = [0; $limbs * LIMB_BYTES];
+                self.write_le_bytes(&mut out);
+                out
+            }
+        }
+    )*};
+}
+
+impl_bytes!(3, 4, 6, 8, 9);
+
+#[cfg(feature = "std")]
+mod mpi_conv {
+    use super::{Uint, LIMB_BYTES};
+    use crate::mpi::Mpi;
+
+    impl<const LIMBS: usize> From<&Uint<LIMBS>> for Mpi {
+        fn from(value: &Uint<LIMBS>) -> Self {
+            let mut bytes = vec![0; LIMBS * LIMB_BYTES];
+            value.write_be_bytes(&mut bytes);
+            Mpi::from_bytes_be(&bytes)
+        }
+    }
+
+    impl<const LIMBS: usize> TryFrom<&Mpi> for Uint<LIMBS> {
+        type Error = String;
+
+        fn try_from(value: &Mpi) -> Result<Self, Self::Error> {
+            let digits = value.digits();
+            let used = digits.iter().rposition(|&d| d != 0).map_or(0, |i| i + 1);
+            if value.is_negative() || used > LIMBS {
+                return Err("Value out of range".into());
+            }
+            let mut limbs = [0; LIMBS];
+            limbs[..used].copy_from_slice(&digits[..used]);
+            Ok(Uint::from_limbs(limbs))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::uint::{U192, U256, U512};
+        use rand_chacha::{
+            rand_core::{RngCore, SeedableRng},
+            ChaCha20Rng,
+        };
+
+        // P-256 group order
+        const N_HEX: &str = "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551";
+
+        fn random<const LIMBS: usize>(rng: &mut impl RngCore) -> Uint<LIMBS> {
+            let mut limbs = [0; LIMBS];
+            limbs.iter_mut().for_each(|l| *l = rng.next_u64());
+            Uint::from_limbs(limbs)
+        }
+
+        #[test]
+        fn conversions() {
+            let n = Mpi::from_hex(N_HEX).unwrap();
+
+            let u = U256::try_from(&n).unwrap();
+
+            assert_eq!(hex::encode(u.to_be_bytes()), N_HEX);
+            assert_eq!(Mpi::from(&u), n);
+            assert!(U192::try_from(&n).is_err());
+            assert!(U256::try_from(&-n).is_err());
+            assert_eq!(U512::try_from(&Mpi::new()).unwrap(), U512::ZERO);
+        }
+
+        #[test]
+        fn modular_ops_match_mpi() {
+            let mut rng = ChaCha20Rng::seed_from_u64(42);
+            let n_mpi = Mpi::from_hex(N_HEX).unwrap();
+            let n = U256::try_from(&n_mpi).unwrap();
+
+            for _ in 0..50 {
+                let a = random::<4>(&mut rng).rem(&n);
+                let b = random::<4>(&mut rng).rem(&n);
+                let (a_mpi, b_mpi) = (Mpi::from(&a), Mpi::from(&b));
+
+                let expected = |x: Mpi| U256::try_from(&x.rem(&n_mpi)).unwrap();
+                assert_eq!(a.add_mod(&b, &n), expected(&a_mpi + &b_mpi));
+                assert_eq!(a.sub_mod(&b, &n), expected(&(&a_mpi - &b_mpi) + &n_mpi));
+                assert_eq!(a.mul_mod(&b, &n), expected(&a_mpi * &b_mpi));
+            }
+        }
+
+        #[test]
+        fn mul_wide_matches_mpi() {
+            let mut rng = ChaCha20Rng::seed_from_u64(42);
+
+            for _ in 0..50 {
+                let a = random::<8>(&mut rng);
+                let b = random::<8>(&mut rng);
+
+                let (lo, hi) = a.mul_wide(&b);
+                let expected = &Mpi::from(&a) * &Mpi::from(&b);
+
+                assert_eq!(&(Mpi::from(&hi) << U512::BITS) + &Mpi::from(&lo), expected);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand_chacha::{
+        rand_core::{RngCore, SeedableRng},
+        ChaCha20Rng,
+    };
+
+    // P-256 group order
+    const N_BYTES: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xbc, 0xe6, 0xfa, 0xad, 0xa7, 0x17, 0x9e, 0x84, 0xf3, 0xb9, 0xca, 0xc2, 0xfc, 0x63,
+        0x25, 0x51,
+    ];
+
+    fn to_u128(x: &Uint<2>) -> u128 {
+        x.as_limbs()[0] as u128 | (x.as_limbs()[1] as u128) << 64
+    }
+
+    fn from_u128(x: u128) -> Uint<2> {
+        Uint::from_limbs([x as u64, (x >> 64) as u64])
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let n = U256::from_be_bytes(N_BYTES);
+
+        assert_eq!(n.to_be_bytes(), N_BYTES);
+        assert_eq!(n.as_limbs()[3], 0xffffffff00000000);
+        assert_eq!(U256::from_le_bytes(n.to_le_bytes()), n);
+        assert_eq!(U256::from_be_slice(&[1, 0]).unwrap(), U256::from(256));
+        assert!(U256::from_be_slice(&[0; 33]).is_err());
+    }
+
+    #[test]
+    fn overflow() {
+        let (s, carry) = U256::MAX.overflowing_add(&U256::ONE);
+        assert!(s.is_zero() && carry);
+
+        let (d, borrow) = U256::ZERO.overflowing_sub(&U256::ONE);
+        assert_eq!(d, U256::MAX);
+        assert!(borrow);
+
+        let (lo, hi) = U256::MAX.mul_wide(&U256::MAX);
+        assert_eq!(lo, U256::ONE);
+        assert_eq!(hi, U256::MAX.wrapping_sub(&U256::ONE));
+    }
+
+    #[test]
+    fn compare() {
+        let a = U256::from(5);
+        let b = U256::from_limbs([0, 0, 0, 1]);
+
+        assert!(a.ct_lt(&b) && !b.ct_lt(&a) && !a.ct_lt(&a));
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(b.bits(), 193);
+        assert!(b.bit(192) && !b.bit(191) && !b.bit(1000));
+        assert_eq!(U256::select(&a, &b, true), b);
+        assert_eq!(U256::select(&a, &b, false), a);
+    }
+
+    #[test]
+    fn arithmetic_matches_u128() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+
+        for _ in 0..200 {
+            let m = rng.next_u64() as u128 | 1;
+            let a = rng.next_u64() as u128 % m;
+            let b = rng.next_u64() as u128 % m;
+            let (ua, ub, um) = (from_u128(a), from_u128(b), from_u128(m));
+
+            assert_eq!(to_u128(&ua.add_mod(&ub, &um)), (a + b) % m);
+            assert_eq!(to_u128(&ua.sub_mod(&ub, &um)), (a + m - b) % m);
+            assert_eq!(to_u128(&ua.mul_mod(&ub, &um)), a * b % m);
+            assert_eq!(to_u128(&ua.neg_mod(&um)), (m - a) % m);
+            assert_eq!(to_u128(&ua.wrapping_mul(&ub)), a * b);
+
+            let x = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+            let y = (rng.next_u64() as u128) << 64 | rng.next_u64() as u128;
+            assert_eq!(
+                to_u128(&from_u128(x).wrapping_add(&from_u128(y))),
+                x.wrapping_add(y)
+            );
+            assert_eq!(
+                to_u128(&from_u128(x).wrapping_sub(&from_u128(y))),
+                x.wrapping_sub(y)
+            );
+            assert_eq!(to_u128(&from_u128(x).rem(&um)), x % m);
+        }
+    }
+
+    #[test]
+    fn reduce_wide() {
+        let n = U256::from_be_bytes(N_BYTES);
+
+        // (n - 1)^2 mod n = 1
+        let n_minus_one = n.wrapping_sub(&U256::ONE);
+        assert_eq!(n_minus_one.mul_mod(&n_minus_one, &n), U256::ONE);
+        // 2^512 - 1 mod n
+        let r = U256::reduce_wide(&U256::MAX, &U256::MAX, &n);
+        assert_eq!(
+            r.as_limbs(),
+            &[
+                0x83244c95be79eea1,
+                0x4699799c49bd6fa6,
+                0x2845b2392b6bec59,
+                0x66e12d94f3d95620
+            ]
+        );
+    }
+}
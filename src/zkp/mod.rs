@@ -0,0 +1,106 @@
+//! Zero-knowledge building blocks over the `ec` groups.
+//!
+//! Proofs are made non-interactive with the Fiat-Shamir transform: the
+//! verifier challenges are derived from a `Transcript` of the statement and
+//! of the prover messages, hashed with SHA-256.
+
+use crate::{
+    ec::{Group, Point},
+    mpi::Mpi,
+    sha256::Sha256,
+};
+
+mod pedersen;
+mod proofs;
+mod transcript;
+
+pub use pedersen::{Commitment, Pedersen};
+pub use proofs::{DleqProof, DlogProof};
+pub use transcript::Transcript;
+
+/// SHA-256 output expanded with a counter, as many bytes as requested.
+fn expand(parts: &[&[u8]], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + 32);
+    let mut counter = 0u32;
+    while out.len() < len {
+        let mut ctx = Sha256::new();
+        ctx.update(counter.to_be_bytes());
+        for part in parts {
+            ctx.update(part);
+        }
+        out.extend_from_slice(&ctx.digest());
+        counter += 1;
+    }
+    out.truncate(len);
+    out
+}
+
+/// Scalar modulo the group order from the hash of the concatenation of
+/// `parts`. The hash is 128 bits longer than the order, so the result is
+/// close to uniform.
+pub fn hash_to_scalar(group: &Group, parts: &[&[u8]]) -> Mpi {
+    let n = group.order();
+    Mpi::from_bytes_be(&expand(parts, n.bytes_count() + 16)) % n
+}
+
+/// Point of the generator subgroup derived from `label`, whose discrete log
+/// to any other point is unknown.
+///
+/// Candidate `x` coordinates are hashed until one is on the curve (try and
+/// increment), the running time is thus variable and `label` must be public.
+pub fn hash_to_point(group: &Group, label: &[u8]) -> Point {
+    let p = group.p();
+    let len = p.bytes_count();
+    // Bits of the leading byte exceeding the field size.
+    let mask = 0xff >> (8 * len - p.bits_count());
+    for i in 0u32.. {
+        // Compressed encoding with an even y.
+        let mut enc = vec![0x02];
+        enc.extend(expand(&[label, &i.to_be_bytes()], len));
+        enc[1] &= mask;
+        if let Ok(point) = Point::from_sec1(group, &enc) {
+            let point = group.mul(&group.cofactor(), &point);
+            if !point.is_identity() {
+                return point;
+            }
+        }
+    }
+    unreachable!("Half of the field elements are valid x coordinates")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::{toy_group, Curve};
+
+    #[test]
+    fn scalars() {
+        let grp = Group::new(Curve::Secp256r1);
+
+        let x = hash_to_scalar(&grp, &[b"vote", b"1"]);
+        assert!(&x < grp.order());
+        assert_eq!(x, hash_to_scalar(&grp, &[b"vo", b"te1"]));
+        assert_ne!(x, hash_to_scalar(&grp, &[b"vote", b"2"]));
+        assert_eq!(expand(&[b"x"], 70).len(), 70);
+        assert_eq!(expand(&[b"x"], 70)[..32], expand(&[b"x"], 32)[..]);
+    }
+
+    #[test]
+    fn points() {
+        for curve in [Curve::Secp256r1, Curve::Secp256k1, Curve::Secp521r1] {
+            let grp = Group::new(curve);
+
+            let h = hash_to_point(&grp, b"H");
+            assert!(grp.is_on_curve(&h));
+            assert_eq!(h, hash_to_point(&grp, b"H"));
+            assert_ne!(h, hash_to_point(&grp, b"H2"));
+            assert_ne!(&h, grp.generator());
+        }
+
+        let grp = toy_group();
+        for label in [&b"a"[..], b"b", b"c", b"d"] {
+            let h = hash_to_point(&grp, label);
+            assert!(grp.mul(grp.order(), &h).is_identity());
+        }
+    }
+}
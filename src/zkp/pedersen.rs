@@ -0,0 +1,147 @@
+use super::hash_to_point;
+use crate::{
+    ec::{Group, Point},
+    mpi::Mpi,
+};
+use rand_core::{CryptoRng, RngCore};
+
+/// Pedersen commitment `v * G + r * H` to the value `v` with blinding `r`.
+///
+/// Commitments are perfectly hiding and binding as long as the discrete log
+/// of `H` to `G` is unknown. They are additively homomorphic, the sum of two
+/// commitments opens to the sum of the values and of the blindings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Commitment(Point);
+
+impl Commitment {
+    pub fn point(&self) -> &Point {
+        &self.0
+    }
+}
+
+/// Commitment parameters, the group and its second generator `H`.
+#[derive(Clone, Debug)]
+pub struct Pedersen {
+    group: Group,
+    h: Point,
+}
+
+// Label of the second generator derivation.
+const H_LABEL: &[u8] = b"cry-rs/pedersen/H";
+
+impl Pedersen {
+    /// Parameters with `H` hashed from the group generator, so that nobody
+    /// knows its discrete log.
+    pub fn new(group: Group) -> Self {
+        let g = group.generator().to_sec1(&group, true);
+        let h = hash_to_point(&group, &[H_LABEL, &g].concat());
+        Pedersen { group, h }
+    }
+
+    /// Parameters with a given second generator, which must be generated in
+    /// a way ensuring that its discrete log is unknown.
+    pub fn with_generator(group: Group, h: Point) -> Result<Self, String> {
+        if !group.is_on_curve(&h)
+            || &h == group.generator()
+            || !group.mul(group.order(), &h).is_identity()
+        {
+            return Err("Invalid generator".into());
+        }
+        Ok(Pedersen { group, h })
+    }
+
+    pub fn group(&self) -> &Group {
+        &self.group
+    }
+
+    pub fn h(&self) -> &Point {
+        &self.h
+    }
+
+    /// Commitment to `value` with the given `blinding`, both are taken
+    /// modulo the group order.
+    pub fn commit(&self, value: &Mpi, blinding: &Mpi) -> Commitment {
+        let n = self.group.order();
+        let v = self.group.mul_generator(&value.rem_euclid(n));
+        let r = self.group.mul(&blinding.rem_euclid(n), &self.h);
+        Commitment(self.group.add(&v, &r))
+    }
+
+    /// Commitment to `value` with a random blinding, which is returned to
+    /// open the commitment later.
+    pub fn commit_with_rng<R: RngCore + CryptoRng>(
+        &self,
+        value: &Mpi,
+        rng: &mut R,
+    ) -> (Commitment, Mpi) {
+        let blinding = self.group.random_scalar(rng);
+        (self.commit(value, &blinding), blinding)
+    }
+
+    /// Checks that `commitment` opens to `value` with `blinding`.
+    pub fn open(&self, commitment: &Commitment, value: &Mpi, blinding: &Mpi) -> bool {
+        &self.commit(value, blinding) == commitment
+    }
+
+    /// Commitment to the sum of the values, with the sum of the blindings.
+    pub fn add(&self, c1: &Commitment, c2: &Commitment) -> Commitment {
+        Commitment(self.group.add(&c1.0, &c2.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::Curve;
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    #[test]
+    fn commit_open() {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let pedersen = Pedersen::new(Group::new(Curve::Secp256r1));
+        let value = Mpi::from(1i64);
+
+        let (c, r) = pedersen.commit_with_rng(&value, &mut rng);
+        assert!(pedersen.open(&c, &value, &r));
+        assert!(!pedersen.open(&c, &Mpi::new(), &r));
+        assert!(!pedersen.open(&c, &value, &(&r + 1u64)));
+        // Hiding, the same value with another blinding
+        let (c2, r2) = pedersen.commit_with_rng(&value, &mut rng);
+        assert_ne!(c, c2);
+        assert!(pedersen.open(&c2, &value, &r2));
+        // Values are taken modulo the order
+        let n = pedersen.group().order();
+        assert_eq!(pedersen.commit(&(&value + n), &r), c);
+    }
+
+    #[test]
+    fn homomorphic() {
+        let mut rng = ChaCha20Rng::seed_from_u64(7);
+        let pedersen = Pedersen::new(Group::new(Curve::Secp256k1));
+        let votes = [1i64, 0, 1, 1, 0];
+
+        let mut tally = pedersen.commit(&Mpi::new(), &Mpi::new());
+        let mut blinding = Mpi::new();
+        for vote in votes {
+            let (c, r) = pedersen.commit_with_rng(&Mpi::from(vote), &mut rng);
+            tally = pedersen.add(&tally, &c);
+            blinding = &blinding + &r;
+        }
+        assert!(pedersen.open(&tally, &Mpi::from(3i64), &blinding));
+    }
+
+    #[test]
+    fn generators() {
+        let grp = Group::new(Curve::Secp256r1);
+        let pedersen = Pedersen::new(grp.clone());
+
+        assert!(grp.is_on_curve(pedersen.h()));
+        assert_ne!(pedersen.h(), grp.generator());
+        assert_eq!(Pedersen::new(grp.clone()).h(), pedersen.h());
+        let h = pedersen.h().clone();
+        assert!(Pedersen::with_generator(grp.clone(), h).is_ok());
+        let g = grp.generator().clone();
+        assert!(Pedersen::with_generator(grp.clone(), g).is_err());
+        assert!(Pedersen::with_generator(grp, Point::identity()).is_err());
+    }
+}
@@ -0,0 +1,276 @@
+use super::Transcript;
+use crate::{
+    ec::{Group, Point},
+    mpi::Mpi,
+};
+use rand_core::{CryptoRng, RngCore};
+
+// Statement points are validated before use, the identity is never valid.
+// With a cofactor, the points must also be in the subgroup of order `n`, or a
+// prover could add small order components to `t` and `Y`.
+fn valid_points(group: &Group, points: &[&Point]) -> bool {
+    let prime_order = group.cofactor() == Mpi::from(1u64);
+    points
+        .iter()
+        .all(|p| group.is_on_curve(p) && (prime_order || group.mul(group.order(), p).is_identity()))
+}
+
+/// Proof of knowledge of `x` such that `Y = x * B` (Schnorr protocol).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DlogProof {
+    t: Point,
+    s: Mpi,
+}
+
+impl DlogProof {
+    /// Proof from its commitment `t` and response `s`.
+    pub fn new(t: Point, s: Mpi) -> Self {
+        DlogProof { t, s }
+    }
+
+    pub fn t(&self) -> &Point {
+        &self.t
+    }
+
+    pub fn s(&self) -> &Mpi {
+        &self.s
+    }
+
+    /// Proves the knowledge of `x`, the discrete log of `x * base`.
+    pub fn prove<R: RngCore + CryptoRng>(
+        transcript: &mut Transcript,
+        group: &Group,
+        base: &Point,
+        x: &Mpi,
+        rng: &mut R,
+    ) -> Self {
+        let y = group.mul(&x.rem_euclid(group.order()), base);
+        let k = group.random_scalar(rng);
+        let t = group.mul(&k, base);
+        let c = Self::challenge(transcript, group, base, &y, &t);
+        let s = (&k + &(&c * x)).rem_euclid(group.order());
+        DlogProof { t, s }
+    }
+
+    /// Checks the proof that the prover knows the discrete log of `y` to
+    /// `base`. The transcript must be in the same state as the prover one.
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        group: &Group,
+        base: &Point,
+        y: &Point,
+    ) -> bool {
+        if !valid_points(group, &[base, y, &self.t]) || &self.s >= group.order() {
+            return false;
+        }
+        let c = Self::challenge(transcript, group, base, y, &self.t);
+        // s * B == t + c * Y
+        group.mul(&self.s, base) == group.add(&self.t, &group.mul(&c, y))
+    }
+
+    fn challenge(
+        transcript: &mut Transcript,
+        group: &Group,
+        base: &Point,
+        y: &Point,
+        t: &Point,
+    ) -> Mpi {
+        transcript.append_message(b"proof", b"dlog");
+        transcript.append_point(b"B", group, base);
+        transcript.append_point(b"Y", group, y);
+        transcript.append_point(b"t", group, t);
+        transcript.challenge_scalar(b"c", group)
+    }
+}
+
+/// Proof that `Y1 = x * B1` and `Y2 = x * B2` with the same secret `x`
+/// (Chaum-Pedersen protocol).
+#[derive(Clone, Debug, PartialEq)]
+pub struct DleqProof {
+    t1: Point,
+    t2: Point,
+    s: Mpi,
+}
+
+impl DleqProof {
+    /// Proof from its commitments `t1`, `t2` and response `s`.
+    pub fn new(t1: Point, t2: Point, s: Mpi) -> Self {
+        DleqProof { t1, t2, s }
+    }
+
+    pub fn t1(&self) -> &Point {
+        &self.t1
+    }
+
+    pub fn t2(&self) -> &Point {
+        &self.t2
+    }
+
+    pub fn s(&self) -> &Mpi {
+        &self.s
+    }
+
+    /// Proves that `x * b1` and `x * b2` have the same discrete log.
+    pub fn prove<R: RngCore + CryptoRng>(
+        transcript: &mut Transcript,
+        group: &Group,
+        (b1, b2): (&Point, &Point),
+        x: &Mpi,
+        rng: &mut R,
+    ) -> Self {
+        let x_mod = x.rem_euclid(group.order());
+        let (y1, y2) = (group.mul(&x_mod, b1), group.mul(&x_mod, b2));
+        let k = group.random_scalar(rng);
+        let (t1, t2) = (group.mul(&k, b1), group.mul(&k, b2));
+        let c = Self::challenge(transcript, group, [b1, b2, &y1, &y2, &t1, &t2]);
+        let s = (&k + &(&c * x)).rem_euclid(group.order());
+        DleqProof { t1, t2, s }
+    }
+
+    /// Checks the proof that `y1` and `y2` have the same discrete log to
+    /// `b1` and `b2` respectively.
+    pub fn verify(
+        &self,
+        transcript: &mut Transcript,
+        group: &Group,
+        (b1, b2): (&Point, &Point),
+        (y1, y2): (&Point, &Point),
+    ) -> bool {
+        let points = [b1, b2, y1, y2, &self.t1, &self.t2];
+        if !valid_points(group, &points) || &self.s >= group.order() {
+            return false;
+        }
+        let c = Self::challenge(transcript, group, points);
+        // s * B1 == t1 + c * Y1 and s * B2 == t2 + c * Y2
+        group.mul(&self.s, b1) == group.add(&self.t1, &group.mul(&c, y1))
+            && group.mul(&self.s, b2) == group.add(&self.t2, &group.mul(&c, y2))
+    }
+
+    // Points are B1, B2, Y1, Y2, t1, t2.
+    fn challenge(t: &mut Transcript, group: &Group, points: [&Point; 6]) -> Mpi {
+        t.append_message(b"proof", b"dleq");
+        for (label, point) in [b"B1", b"B2", b"Y1", b"Y2", b"t1", b"t2"]
+            .iter()
+            .zip(points)
+        {
+            t.append_point(*label, group, point);
+        }
+        t.challenge_scalar(b"c", group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ec::{toy_group, Curve},
+        zkp::hash_to_point,
+    };
+    use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+    fn setup() -> (Group, ChaCha20Rng) {
+        (Group::new(Curve::Secp256r1), ChaCha20Rng::seed_from_u64(42))
+    }
+
+    #[test]
+    fn dlog() {
+        let (grp, mut rng) = setup();
+        let g = grp.generator();
+        let x = grp.random_scalar(&mut rng);
+        let y = grp.mul_generator(&x);
+
+        let proof = DlogProof::prove(&mut Transcript::new(b"test"), &grp, g, &x, &mut rng);
+
+        assert!(proof.verify(&mut Transcript::new(b"test"), &grp, g, &y));
+        assert!(!proof.verify(&mut Transcript::new(b"other"), &grp, g, &y));
+        assert!(!proof.verify(&mut Transcript::new(b"test"), &grp, g, g));
+        let h = hash_to_point(&grp, b"H");
+        assert!(!proof.verify(&mut Transcript::new(b"test"), &grp, &h, &y));
+        let forged = DlogProof::new(proof.t().clone(), proof.s() + 1u64);
+        assert!(!forged.verify(&mut Transcript::new(b"test"), &grp, g, &y));
+        let forged = DlogProof::new(Point::identity(), proof.s().clone());
+        assert!(!forged.verify(&mut Transcript::new(b"test"), &grp, g, &y));
+    }
+
+    #[test]
+    fn dlog_bound_to_context() {
+        let (grp, mut rng) = setup();
+        let g = grp.generator();
+        let x = grp.random_scalar(&mut rng);
+        let y = grp.mul_generator(&x);
+        let mut transcript = Transcript::new(b"test");
+        transcript.append_message(b"voter", b"alice");
+
+        let proof = DlogProof::prove(&mut transcript.clone(), &grp, g, &x, &mut rng);
+
+        assert!(proof.verify(&mut transcript, &grp, g, &y));
+        let mut other = Transcript::new(b"test");
+        other.append_message(b"voter", b"bob");
+        assert!(!proof.verify(&mut other, &grp, g, &y));
+    }
+
+    #[test]
+    fn dleq() {
+        let (grp, mut rng) = setup();
+        let b1 = grp.generator().clone();
+        let b2 = hash_to_point(&grp, b"B2");
+        let x = grp.random_scalar(&mut rng);
+        let (y1, y2) = (grp.mul(&x, &b1), grp.mul(&x, &b2));
+        let bases = (&b1, &b2);
+
+        let proof = DleqProof::prove(&mut Transcript::new(b"test"), &grp, bases, &x, &mut rng);
+
+        assert!(proof.verify(&mut Transcript::new(b"test"), &grp, bases, (&y1, &y2)));
+        assert!(!proof.verify(&mut Transcript::new(b"test"), &grp, bases, (&y2, &y1)));
+        assert!(!proof.verify(&mut Transcript::new(b"test"), &grp, (&b2, &b1), (&y1, &y2)));
+        // Different logs
+        let y3 = grp.mul(&(&x + 1u64), &b2);
+        assert!(!proof.verify(&mut Transcript::new(b"test"), &grp, bases, (&y1, &y3)));
+        let forged = DleqProof::new(proof.t2().clone(), proof.t1().clone(), proof.s().clone());
+        assert!(!forged.verify(&mut Transcript::new(b"test"), &grp, bases, (&y1, &y2)));
+    }
+
+    #[test]
+    fn small_order_components() {
+        let grp = toy_group();
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        let g = grp.generator();
+        let int = |x: u64| Mpi::from(x);
+        // Point of order 4
+        let small = grp.mul(&int(2521), &Point::new(int(2), int(557)));
+        assert!(!small.is_identity() && grp.mul(&int(4), &small).is_identity());
+
+        let x = grp.random_scalar(&mut rng);
+        let y = grp.mul_generator(&x);
+        let proof = DlogProof::prove(&mut Transcript::new(b"test"), &grp, g, &x, &mut rng);
+        assert!(proof.verify(&mut Transcript::new(b"test"), &grp, g, &y));
+
+        // t and Y with the same small component pass the equation when
+        // c = -1 mod 4.
+        let y = grp.add(&y, &small);
+        let forged = loop {
+            let k = grp.random_scalar(&mut rng);
+            let t = grp.add(&grp.mul_generator(&k), &small);
+            let c = DlogProof::challenge(&mut Transcript::new(b"test"), &grp, g, &y, &t);
+            if c.rem_euclid(&int(4)) == int(3) {
+                let s = (&k + &(&c * &x)).rem_euclid(grp.order());
+                break DlogProof::new(t, s);
+            }
+        };
+        let c = DlogProof::challenge(&mut Transcript::new(b"test"), &grp, g, &y, forged.t());
+        assert_eq!(
+            grp.mul_generator(forged.s()),
+            grp.add(forged.t(), &grp.mul(&c, &y))
+        );
+        assert!(!forged.verify(&mut Transcript::new(b"test"), &grp, g, &y));
+
+        let b2 = hash_to_point(&grp, b"B2");
+        let bases = (g, &b2);
+        let (y1, y2) = (grp.mul_generator(&x), grp.mul(&x, &b2));
+        let proof = DleqProof::prove(&mut Transcript::new(b"test"), &grp, bases, &x, &mut rng);
+        assert!(proof.verify(&mut Transcript::new(b"test"), &grp, bases, (&y1, &y2)));
+        let y2 = grp.add(&y2, &small);
+        assert!(!proof.verify(&mut Transcript::new(b"test"), &grp, bases, (&y1, &y2)));
+    }
+}
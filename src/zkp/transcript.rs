@@ -0,0 +1,80 @@
+use super::hash_to_scalar;
+use crate::{
+    ec::{Group, Point},
+    mpi::Mpi,
+};
+
+/// Fiat-Shamir transcript of a protocol run.
+///
+/// Messages are appended with a label and their length, so that different
+/// sequences of messages never produce the same transcript. Each challenge
+/// is derived from everything appended so far and is then appended itself.
+#[derive(Clone, Debug)]
+pub struct Transcript {
+    data: Vec<u8>,
+}
+
+impl Transcript {
+    /// Transcript of the protocol named `label`.
+    pub fn new(label: &[u8]) -> Self {
+        let mut transcript = Transcript { data: Vec::new() };
+        transcript.append_message(b"protocol", label);
+        transcript
+    }
+
+    pub fn append_message(&mut self, label: &[u8], message: &[u8]) {
+        for bytes in [label, message] {
+            self.data.extend((bytes.len() as u64).to_be_bytes());
+            self.data.extend(bytes);
+        }
+    }
+
+    pub fn append_scalar(&mut self, label: &[u8], x: &Mpi) {
+        self.append_message(label, &x.to_bytes_be());
+    }
+
+    /// Appends the compressed SEC 1 encoding of `point`.
+    pub fn append_point(&mut self, label: &[u8], group: &Group, point: &Point) {
+        self.append_message(label, &point.to_sec1(group, true));
+    }
+
+    /// Challenge modulo the group order.
+    pub fn challenge_scalar(&mut self, label: &[u8], group: &Group) -> Mpi {
+        self.append_message(b"challenge", label);
+        let c = hash_to_scalar(group, &[&self.data]);
+        self.append_scalar(label, &c);
+        c
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ec::Curve;
+
+    #[test]
+    fn challenges() {
+        let grp = Group::new(Curve::Secp256r1);
+        let mut t1 = Transcript::new(b"test");
+        t1.append_message(b"m", b"ab");
+        t1.append_point(b"G", &grp, grp.generator());
+        let mut t2 = t1.clone();
+
+        let c1 = t1.challenge_scalar(b"c", &grp);
+        assert_eq!(c1, t2.challenge_scalar(b"c", &grp));
+        // Chained challenges
+        assert_ne!(c1, t1.challenge_scalar(b"c", &grp));
+
+        // Same bytes, different framing
+        let mut t3 = Transcript::new(b"test");
+        t3.append_message(b"m", b"a");
+        t3.append_message(b"", b"b");
+        t3.append_point(b"G", &grp, grp.generator());
+        assert_ne!(c1, t3.challenge_scalar(b"c", &grp));
+
+        let mut t4 = Transcript::new(b"other");
+        t4.append_message(b"m", b"ab");
+        t4.append_point(b"G", &grp, grp.generator());
+        assert_ne!(c1, t4.challenge_scalar(b"c", &grp));
+    }
+}